@@ -0,0 +1,77 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::models::PacketAnalysis;
+use crate::push_warning;
+
+/// Watches ARP replies for an IPv4 address claimed by more than one MAC.
+#[derive(Default)]
+pub struct ArpConflictTracker {
+    owners: HashMap<String, String>,
+    reported: HashSet<String>,
+}
+
+impl ArpConflictTracker {
+    /// Records the sender of an ARP reply, warning the first time its
+    /// address is claimed by a MAC other than the one seen before.
+    pub fn observe(&mut self, analysis: &PacketAnalysis, warnings: &mut Vec<String>) {
+        let Some(arp) = &analysis.layers.arp else {
+            return;
+        };
+        if arp.operation != 2 {
+            return;
+        }
+        let owner = self
+            .owners
+            .entry(arp.sender_ip.clone())
+            .or_insert_with(|| arp.sender_mac.clone());
+        if *owner != arp.sender_mac && self.reported.insert(arp.sender_ip.clone()) {
+            push_warning(
+                warnings,
+                format!(
+                    "IP {} claimed by {} and {}",
+                    arp.sender_ip, owner, arp.sender_mac
+                ),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ArpHeader, DecodedLayers};
+
+    fn reply(sender_mac: &str) -> PacketAnalysis {
+        PacketAnalysis {
+            source: "10.0.0.5".to_string(),
+            destination: "10.0.0.1".to_string(),
+            protocol: "ARP".to_string(),
+            summary: String::new(),
+            layers: DecodedLayers {
+                arp: Some(ArpHeader {
+                    operation: 2,
+                    sender_mac: sender_mac.to_string(),
+                    sender_ip: "10.0.0.5".to_string(),
+                    target_mac: "00:00:00:00:00:01".to_string(),
+                    target_ip: "10.0.0.1".to_string(),
+                }),
+                ..DecodedLayers::default()
+            },
+        }
+    }
+
+    #[test]
+    fn warns_once_per_conflicting_address() {
+        let mut tracker = ArpConflictTracker::default();
+        let mut warnings = Vec::new();
+        tracker.observe(&reply("00:11:22:33:44:55"), &mut warnings);
+        tracker.observe(&reply("00:11:22:33:44:55"), &mut warnings);
+        assert!(warnings.is_empty());
+        tracker.observe(&reply("AA:BB:CC:DD:EE:FF"), &mut warnings);
+        tracker.observe(&reply("AA:BB:CC:DD:EE:FF"), &mut warnings);
+        assert_eq!(
+            warnings,
+            ["IP 10.0.0.5 claimed by 00:11:22:33:44:55 and AA:BB:CC:DD:EE:FF"]
+        );
+    }
+}
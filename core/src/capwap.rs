@@ -0,0 +1,71 @@
+use crate::{ARROW, DECAP_LIMIT_NOTE, Decap, analyze_ethernet_frame};
+
+/// Wireless binding ID for IEEE 802.11 (RFC 5416).
+const WBID_IEEE_80211: u32 = 1;
+
+/// Summarizes a CAPWAP data packet (RFC 5415) as `CAPWAP data → <inner>`,
+/// dissecting an encapsulated 802.3 frame with the Ethernet analyzer.
+/// Native 802.11 frames are labelled but not dissected.
+pub fn parse_capwap(payload: &[u8], decap: Decap) -> Option<String> {
+    let header = payload.get(..8)?;
+    // Preamble: version 0, type 0 (a DTLS-protected packet has type 1).
+    if header[0] != 0 {
+        return None;
+    }
+    let word = u32::from_be_bytes([header[0], header[1], header[2], header[3]]);
+    let header_len = ((word >> 19) & 0x1F) as usize * 4;
+    let wbid = (word >> 9) & 0x1F;
+    let native = word & 0x100 != 0;
+    let fragment = word & 0x80 != 0;
+    if header_len < 8 || header_len > payload.len() {
+        return None;
+    }
+    let inner = &payload[header_len..];
+    let description = if inner.is_empty() {
+        "keep-alive".to_string()
+    } else if fragment {
+        format!("fragment ({} bytes)", inner.len())
+    } else if !native {
+        match decap.next() {
+            Some(decap) => analyze_ethernet_frame(inner, decap.options, decap).summary,
+            None => format!("Ethernet frame ({} bytes){DECAP_LIMIT_NOTE}", inner.len()),
+        }
+    } else if wbid == WBID_IEEE_80211 {
+        format!("IEEE 802.11 frame ({} bytes)", inner.len())
+    } else {
+        format!("binding {wbid} frame ({} bytes)", inner.len())
+    };
+    Some(format!("CAPWAP data {ARROW} {description}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn rejects_header_length_past_payload() {
+        // HLEN 4 words, 802.11 binding, T bit set.
+        let mut packet = vec![0x00, 0x20, 0x03, 0x00];
+        packet.extend_from_slice(&[0; 12]);
+        packet.extend_from_slice(&[0x08, 0x00]);
+        assert_eq!(
+            parse_capwap(&packet, Decap::default()).as_deref(),
+            Some("CAPWAP data \u{2192} IEEE 802.11 frame (2 bytes)")
+        );
+        packet[1] = 0xF8;
+        assert!(parse_capwap(&packet, Decap::default()).is_none());
+    }
+    #[test]
+    fn dissects_inner_frame_with_caller_options() {
+        // HLEN 2 words, 802.3 payload: a 60-byte frame of unknown EtherType.
+        let mut packet = vec![0x00, 0x10, 0x02, 0x00, 0, 0, 0, 0];
+        packet.extend_from_slice(&[0; 60]);
+        let options = crate::options::ProcessOptions {
+            jumbo_frame_threshold: 40,
+            ..Default::default()
+        };
+        let summary = parse_capwap(&packet, Decap::new(&options)).unwrap();
+        assert!(summary.ends_with(" [jumbo]"), "{summary}");
+        let summary = parse_capwap(&packet, Decap::default()).unwrap();
+        assert!(!summary.contains("[jumbo]"));
+    }
+}
@@ -1,5 +1,8 @@
 use std::convert::TryInto;
 
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
 #[derive(Clone, Copy)]
 pub enum CaptureFormat {
     Raw,
@@ -7,6 +10,16 @@ pub enum CaptureFormat {
     PcapNg,
 }
 
+impl CaptureFormat {
+    pub fn name(self) -> &'static str {
+        match self {
+            CaptureFormat::Raw => "raw",
+            CaptureFormat::Pcap => "PCAP",
+            CaptureFormat::PcapNg => "PCAPNG",
+        }
+    }
+}
+
 pub fn detect_format(data: &[u8]) -> CaptureFormat {
     if data.len() < 4 {
         return CaptureFormat::Raw;
@@ -1,6 +1,4 @@
-use crate::DecodedLayers;
-
-const ARROW: &str = "\u{2192}";
+use crate::{ARROW, DecodedLayers};
 
 pub fn build_summary_from_layers(layers: &DecodedLayers, default: String) -> String {
     if let Some(icmp) = &layers.icmp {
@@ -20,6 +18,52 @@ pub fn build_summary_from_layers(layers: &DecodedLayers, default: String) -> Str
     default
 }
 
+/// Builds a Wireshark-style protocol path such as `eth:ip:tcp:http` from the
+/// decoded layers, ending with the packet's protocol when it names a layer
+/// above those. Packets with nothing decoded report `raw`.
+pub fn protocol_stack(layers: Option<&DecodedLayers>, protocol: &str) -> String {
+    let mut tags: Vec<String> = Vec::new();
+    if let Some(layers) = layers {
+        if let Some(ethernet) = &layers.ethernet {
+            tags.push("eth".into());
+            tags.extend(ethernet.vlan.iter().map(|_| "vlan".to_string()));
+        }
+        if !layers.mpls.is_empty() {
+            tags.push("mpls".into());
+        }
+        if layers.arp.is_some() {
+            tags.push("arp".into());
+        }
+        if layers.ipv4.is_some() {
+            tags.push("ip".into());
+        }
+        if layers.ipv6.is_some() {
+            tags.push("ipv6".into());
+        }
+        if layers.tcp.is_some() {
+            tags.push("tcp".into());
+        }
+        if layers.udp.is_some() {
+            tags.push("udp".into());
+        }
+        if let Some(icmp) = &layers.icmp {
+            tags.push(icmp.version.to_ascii_lowercase());
+        }
+        if layers.dns.is_some() {
+            tags.push("dns".into());
+        }
+    }
+    let top = protocol.to_ascii_lowercase();
+    // Generic labels such as "EtherType 0x88CC" or the bare "IP" add nothing.
+    if !top.contains(' ') && top != "ip" && !tags.contains(&top) {
+        tags.push(top);
+    }
+    if tags.is_empty() {
+        return "raw".to_string();
+    }
+    tags.join(":")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -41,6 +85,7 @@ mod tests {
                     header_length: 20,
                     total_length: 20,
                     ttl: 64,
+                    checksum_valid: true,
                 }),
                 ..DecodedLayers::default()
             },
@@ -48,4 +93,23 @@ mod tests {
         );
         assert!(s.contains("echo request"));
     }
+
+    #[test]
+    fn stacks_layer_tags() {
+        let layers = DecodedLayers {
+            ipv4: Some(Ipv4Header {
+                source: "1.1.1.1".into(),
+                destination: "2.2.2.2".into(),
+                protocol: 6,
+                header_length: 20,
+                total_length: 40,
+                ttl: 64,
+                checksum_valid: true,
+            }),
+            ..DecodedLayers::default()
+        };
+        assert_eq!(protocol_stack(Some(&layers), "SCTP"), "ip:sctp");
+        assert_eq!(protocol_stack(Some(&layers), "IP"), "ip");
+        assert_eq!(protocol_stack(None, "LINKTYPE 147"), "raw");
+    }
 }
@@ -0,0 +1,148 @@
+use std::net::Ipv4Addr;
+
+use crate::format_mac;
+
+const OPTIONS_OFFSET: usize = 240;
+const MAGIC_COOKIE: [u8; 4] = [99, 130, 83, 99];
+
+/// Summarizes a DHCP message as its option-53 type plus the assigned
+/// address and client hardware address, e.g.
+/// `DHCP Offer yiaddr=10.0.0.50 chaddr=00:11:22:33:44:55`. Messages without
+/// a DHCP message type are reported as plain BOOTP. An option-55 parameter
+/// request list is appended as ` [param list: 1,3,6]` for fingerprinting.
+pub fn parse_dhcp(payload: &[u8]) -> Option<String> {
+    if payload.len() < OPTIONS_OFFSET {
+        return None;
+    }
+    let op = payload[0];
+    if !(1..=2).contains(&op) {
+        return None;
+    }
+    let options = (payload[236..240] == MAGIC_COOKIE).then(|| &payload[OPTIONS_OFFSET..]);
+    let message_type = options
+        .and_then(|options| find_option(options, 53))
+        .and_then(|value| value.first().copied());
+    let mut summary = match message_type {
+        Some(kind) => format!("DHCP {}", message_type_name(kind)),
+        None if op == 1 => "BOOTP Request".to_string(),
+        None => "BOOTP Reply".to_string(),
+    };
+    let yiaddr = Ipv4Addr::new(payload[16], payload[17], payload[18], payload[19]);
+    if !yiaddr.is_unspecified() {
+        summary.push_str(&format!(" yiaddr={yiaddr}"));
+    }
+    let (htype, hlen) = (payload[1], payload[2] as usize);
+    if htype == 1 && hlen == 6 {
+        summary.push_str(&format!(" chaddr={}", format_mac(&payload[28..34])));
+    }
+    // The order of requested parameters identifies the client OS.
+    if let Some(params) = options.and_then(|options| find_option(options, 55))
+        && !params.is_empty()
+    {
+        let list: Vec<String> = params.iter().map(u8::to_string).collect();
+        summary.push_str(&format!(" [param list: {}]", list.join(",")));
+    }
+    Some(summary)
+}
+
+fn message_type_name(kind: u8) -> String {
+    match kind {
+        1 => "Discover".into(),
+        2 => "Offer".into(),
+        3 => "Request".into(),
+        4 => "Decline".into(),
+        5 => "Ack".into(),
+        6 => "Nak".into(),
+        7 => "Release".into(),
+        8 => "Inform".into(),
+        _ => format!("type {kind}"),
+    }
+}
+
+/// Returns the value of the first option with `code`, stopping at the End
+/// option or the first option that overruns the buffer.
+fn find_option(options: &[u8], code: u8) -> Option<&[u8]> {
+    let mut offset = 0;
+    while offset < options.len() {
+        match options[offset] {
+            0 => offset += 1,
+            255 => return None,
+            option_code => {
+                let length = *options.get(offset + 1)? as usize;
+                let value = options.get(offset + 2..offset + 2 + length)?;
+                if option_code == code {
+                    return Some(value);
+                }
+                offset += 2 + length;
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bootp(op: u8, yiaddr: [u8; 4], options: &[u8]) -> Vec<u8> {
+        let mut payload = vec![0u8; OPTIONS_OFFSET];
+        payload[0] = op;
+        payload[1] = 1;
+        payload[2] = 6;
+        payload[16..20].copy_from_slice(&yiaddr);
+        payload[28..34].copy_from_slice(&[0x00, 0x11, 0x22, 0x33, 0x44, 0x55]);
+        payload[236..240].copy_from_slice(&MAGIC_COOKIE);
+        payload.extend_from_slice(options);
+        payload
+    }
+
+    #[test]
+    fn reports_assigned_address_and_client() {
+        let offer = bootp(2, [10, 0, 0, 50], &[53, 1, 2, 255]);
+        assert_eq!(
+            parse_dhcp(&offer).as_deref(),
+            Some("DHCP Offer yiaddr=10.0.0.50 chaddr=00:11:22:33:44:55")
+        );
+        let discover = bootp(1, [0; 4], &[0, 53, 1, 1, 255]);
+        assert_eq!(
+            parse_dhcp(&discover).as_deref(),
+            Some("DHCP Discover chaddr=00:11:22:33:44:55")
+        );
+    }
+
+    #[test]
+    fn stops_at_option_buffer_boundary() {
+        let ack = bootp(2, [0; 4], &[0, 0, 53, 1, 5]);
+        assert_eq!(
+            parse_dhcp(&ack).as_deref(),
+            Some("DHCP Ack chaddr=00:11:22:33:44:55")
+        );
+        // The message type option claims more bytes than remain.
+        let truncated = bootp(1, [0; 4], &[53, 4, 1]);
+        assert_eq!(
+            parse_dhcp(&truncated).as_deref(),
+            Some("BOOTP Request chaddr=00:11:22:33:44:55")
+        );
+    }
+
+    #[test]
+    fn lists_requested_parameters() {
+        let discover = bootp(1, [0; 4], &[53, 1, 1, 55, 4, 1, 3, 6, 15, 255]);
+        assert_eq!(
+            parse_dhcp(&discover).as_deref(),
+            Some("DHCP Discover chaddr=00:11:22:33:44:55 [param list: 1,3,6,15]")
+        );
+        // A list running past the payload is ignored.
+        let truncated = bootp(1, [0; 4], &[53, 1, 1, 55, 9, 1, 3]);
+        assert_eq!(
+            parse_dhcp(&truncated).as_deref(),
+            Some("DHCP Discover chaddr=00:11:22:33:44:55")
+        );
+    }
+
+    #[test]
+    fn rejects_short_or_non_bootp_payloads() {
+        assert!(parse_dhcp(&[1; 100]).is_none());
+        assert!(parse_dhcp(&bootp(3, [0; 4], &[])).is_none());
+    }
+}
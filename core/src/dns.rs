@@ -0,0 +1,185 @@
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use crate::models::DnsMessage;
+
+/// Pointer jumps allowed while decoding one name, so compression loops in
+/// malformed messages terminate.
+const MAX_POINTER_JUMPS: usize = 16;
+const MAX_NAME_LEN: usize = 255;
+/// Answers decoded per message; the rest are counted but not rendered.
+const MAX_ANSWERS: usize = 16;
+
+/// Decodes the header, first question and answer records of a DNS message.
+/// `framed` strips the 2-byte length prefix used over TCP.
+pub fn parse_dns(payload: &[u8], framed: bool) -> Option<DnsMessage> {
+    let message = if framed { payload.get(2..)? } else { payload };
+    if message.len() < 12 {
+        return None;
+    }
+    let read_u16 = |offset: usize| -> Option<u16> {
+        let bytes = message.get(offset..offset + 2)?;
+        Some(u16::from_be_bytes([bytes[0], bytes[1]]))
+    };
+    let id = read_u16(0)?;
+    let flags = read_u16(2)?;
+    let question_count = read_u16(4)?;
+    let answer_count = read_u16(6)?;
+
+    let mut offset = 12;
+    let mut question = None;
+    for index in 0..question_count {
+        let (name, next) = read_name(message, offset)?;
+        let qtype = read_u16(next)?;
+        let qclass = read_u16(next + 2)?;
+        if index == 0 {
+            question = Some((name, qtype, qclass));
+        }
+        offset = next + 4;
+    }
+
+    let mut answers = Vec::new();
+    for _ in 0..answer_count.min(MAX_ANSWERS as u16) {
+        let (_, next) = read_name(message, offset)?;
+        let rtype = read_u16(next)?;
+        let rdlength = read_u16(next + 8)? as usize;
+        let rdata_start = next + 10;
+        let rdata = message.get(rdata_start..rdata_start + rdlength)?;
+        answers.push(describe_rdata(message, rtype, rdata, rdata_start));
+        offset = rdata_start + rdlength;
+    }
+
+    let (qname, qtype, qclass) = match question {
+        Some((name, qtype, qclass)) => (Some(name), Some(record_type_name(qtype)), Some(qclass)),
+        None => (None, None, None),
+    };
+    Some(DnsMessage {
+        id,
+        response: flags & 0x8000 != 0,
+        opcode: ((flags >> 11) & 0x0F) as u8,
+        rcode: (flags & 0x0F) as u8,
+        qname,
+        qtype,
+        qclass,
+        answers,
+    })
+}
+
+/// Summarizes a message as `DNS A query example.com` or
+/// `DNS response example.com`.
+pub fn describe_dns(dns: &DnsMessage) -> String {
+    let name = dns.qname.as_deref().unwrap_or("<no question>");
+    if dns.response {
+        format!("DNS response {name}")
+    } else {
+        let qtype = dns.qtype.as_deref().unwrap_or("?");
+        format!("DNS {qtype} query {name}")
+    }
+}
+
+/// Decodes a possibly compressed domain name starting at `offset`,
+/// returning it with the offset just past the name in the original position.
+fn read_name(message: &[u8], mut offset: usize) -> Option<(String, usize)> {
+    let mut labels: Vec<String> = Vec::new();
+    let mut name_len = 0;
+    let mut resume = None;
+    let mut jumps = 0;
+    loop {
+        let length = *message.get(offset)? as usize;
+        match length & 0xC0 {
+            0x00 if length == 0 => {
+                offset += 1;
+                break;
+            }
+            0x00 => {
+                let label = message.get(offset + 1..offset + 1 + length)?;
+                name_len += length + 1;
+                if name_len > MAX_NAME_LEN {
+                    return None;
+                }
+                labels.push(String::from_utf8_lossy(label).into_owned());
+                offset += 1 + length;
+            }
+            0xC0 => {
+                jumps += 1;
+                if jumps > MAX_POINTER_JUMPS {
+                    return None;
+                }
+                let low = *message.get(offset + 1)? as usize;
+                resume.get_or_insert(offset + 2);
+                offset = ((length & 0x3F) << 8) | low;
+            }
+            _ => return None,
+        }
+    }
+    let name = if labels.is_empty() {
+        ".".to_string()
+    } else {
+        labels.join(".")
+    };
+    Some((name, resume.unwrap_or(offset)))
+}
+
+fn describe_rdata(message: &[u8], rtype: u16, rdata: &[u8], start: usize) -> String {
+    match rtype {
+        1 if rdata.len() == 4 => Ipv4Addr::new(rdata[0], rdata[1], rdata[2], rdata[3]).to_string(),
+        28 if rdata.len() == 16 => {
+            let octets: [u8; 16] = rdata.try_into().unwrap_or([0; 16]);
+            Ipv6Addr::from(octets).to_string()
+        }
+        2 | 5 | 12 => read_name(message, start)
+            .map(|(name, _)| name)
+            .unwrap_or_else(|| format!("{} (malformed)", record_type_name(rtype))),
+        _ => format!("{} ({} bytes)", record_type_name(rtype), rdata.len()),
+    }
+}
+
+/// Returns the mnemonic for common record types, or `TYPEn` (RFC 3597).
+pub fn record_type_name(rtype: u16) -> String {
+    match rtype {
+        1 => "A".into(),
+        2 => "NS".into(),
+        5 => "CNAME".into(),
+        6 => "SOA".into(),
+        12 => "PTR".into(),
+        15 => "MX".into(),
+        16 => "TXT".into(),
+        28 => "AAAA".into(),
+        33 => "SRV".into(),
+        41 => "OPT".into(),
+        64 => "SVCB".into(),
+        65 => "HTTPS".into(),
+        255 => "ANY".into(),
+        _ => format!("TYPE{rtype}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response() -> Vec<u8> {
+        let mut message = vec![0x12, 0x34, 0x81, 0x80, 0, 1, 0, 2, 0, 0, 0, 0];
+        message.extend_from_slice(b"\x03www\x07example\x03com\x00\x00\x01\x00\x01");
+        // CNAME pointing into the question, then an A record for it.
+        message.extend_from_slice(&[0xC0, 12, 0, 5, 0, 1, 0, 0, 0, 60, 0, 2, 0xC0, 16]);
+        message.extend_from_slice(&[0xC0, 16, 0, 1, 0, 1, 0, 0, 0, 60, 0, 4, 93, 184, 216, 34]);
+        message
+    }
+
+    #[test]
+    fn decodes_question_and_compressed_answers() {
+        let dns = parse_dns(&response(), false).unwrap();
+        assert!(dns.response);
+        assert_eq!(dns.qname.as_deref(), Some("www.example.com"));
+        assert_eq!(dns.qtype.as_deref(), Some("A"));
+        assert_eq!(dns.answers, ["example.com", "93.184.216.34"]);
+        assert_eq!(describe_dns(&dns), "DNS response www.example.com");
+    }
+
+    #[test]
+    fn rejects_pointer_loops() {
+        let mut message = vec![0, 1, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0];
+        message.extend_from_slice(&[0xC0, 12, 0, 1, 0, 1]);
+        assert!(parse_dns(&message, false).is_none());
+    }
+}
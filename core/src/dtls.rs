@@ -0,0 +1,46 @@
+const RECORD_HEADER_LEN: usize = 13;
+
+pub fn parse_dtls(payload: &[u8]) -> Option<String> {
+    if payload.len() < RECORD_HEADER_LEN {
+        return None;
+    }
+    let content = match payload[0] {
+        20 => "Change Cipher Spec",
+        21 => "Alert",
+        22 => "Handshake",
+        23 => "Application Data",
+        24 => "Heartbeat",
+        25 => "Connection ID",
+        _ => return None,
+    };
+    // DTLS versions are the one's complement of their TLS counterparts;
+    // DTLS 1.3 keeps 1.2 on the wire.
+    let version = match u16::from_be_bytes([payload[1], payload[2]]) {
+        0xFEFF => "DTLS1.0",
+        0xFEFD => "DTLS1.2",
+        _ => return None,
+    };
+    let length = u16::from_be_bytes([payload[11], payload[12]]) as usize;
+    if length > payload.len() - RECORD_HEADER_LEN {
+        return None;
+    }
+    Some(format!("{version} {content}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn recognizes_dtls12_handshake() {
+        let mut record = vec![22, 0xFE, 0xFD, 0, 0, 0, 0, 0, 0, 0, 1, 0, 2];
+        record.extend_from_slice(&[1, 0]);
+        assert_eq!(parse_dtls(&record).as_deref(), Some("DTLS1.2 Handshake"));
+    }
+    #[test]
+    fn rejects_tls_versions_and_overlong_records() {
+        let tls = [23, 0x03, 0x03, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+        assert!(parse_dtls(&tls).is_none());
+        let overlong = [23, 0xFE, 0xFD, 0, 0, 0, 0, 0, 0, 0, 0, 0, 9];
+        assert!(parse_dtls(&overlong).is_none());
+    }
+}
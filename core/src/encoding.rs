@@ -0,0 +1,104 @@
+/// Decodes a hex string, ignoring whitespace and line breaks. Both digit
+/// cases are accepted.
+pub fn decode_hex(text: &str) -> Result<Vec<u8>, String> {
+    let digits: Vec<u8> = text
+        .bytes()
+        .filter(|byte| !byte.is_ascii_whitespace())
+        .collect();
+    if !digits.len().is_multiple_of(2) {
+        return Err("Hex input has an odd number of digits".to_string());
+    }
+    digits
+        .chunks_exact(2)
+        .map(|pair| {
+            let value = |digit: u8| (digit as char).to_digit(16);
+            match (value(pair[0]), value(pair[1])) {
+                (Some(high), Some(low)) => Ok((high * 16 + low) as u8),
+                _ => Err(format!(
+                    "Invalid hex digits {:?}",
+                    String::from_utf8_lossy(pair)
+                )),
+            }
+        })
+        .collect()
+}
+
+/// Decodes standard (RFC 4648) base64, ignoring whitespace. Padding is
+/// optional.
+pub fn decode_base64(text: &str) -> Result<Vec<u8>, String> {
+    let symbols: Vec<u8> = text
+        .bytes()
+        .filter(|byte| !byte.is_ascii_whitespace())
+        .collect();
+    let unpadded = symbols
+        .strip_suffix(b"==")
+        .or_else(|| symbols.strip_suffix(b"="))
+        .unwrap_or(&symbols);
+    if unpadded.len() % 4 == 1 {
+        return Err("Base64 input has an invalid length".to_string());
+    }
+    let mut decoded = Vec::with_capacity(unpadded.len() * 3 / 4);
+    for chunk in unpadded.chunks(4) {
+        let mut bits = 0u32;
+        for symbol in chunk {
+            let value = match symbol {
+                b'A'..=b'Z' => symbol - b'A',
+                b'a'..=b'z' => symbol - b'a' + 26,
+                b'0'..=b'9' => symbol - b'0' + 52,
+                b'+' => 62,
+                b'/' => 63,
+                _ => return Err(format!("Invalid base64 character {:?}", *symbol as char)),
+            };
+            bits = (bits << 6) | u32::from(value);
+        }
+        bits <<= 6 * (4 - chunk.len() as u32);
+        let bytes = bits.to_be_bytes();
+        decoded.extend_from_slice(&bytes[1..chunk.len()]);
+    }
+    Ok(decoded)
+}
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Encodes bytes as padded standard (RFC 4648) base64.
+pub fn encode_base64(data: &[u8]) -> String {
+    let mut encoded = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let mut bytes = [0u8; 3];
+        bytes[..chunk.len()].copy_from_slice(chunk);
+        let bits = u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]);
+        for index in 0..4 {
+            if index <= chunk.len() {
+                let symbol = (bits >> (18 - 6 * index)) & 0x3F;
+                encoded.push(BASE64_ALPHABET[symbol as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn decodes_hex_with_whitespace() {
+        assert_eq!(decode_hex("d4 c3\nB2a1").unwrap(), [0xD4, 0xC3, 0xB2, 0xA1]);
+        assert!(decode_hex("abc").is_err());
+        assert!(decode_hex("zz").is_err());
+    }
+    #[test]
+    fn decodes_padded_and_unpadded_base64() {
+        assert_eq!(decode_base64("TWFu").unwrap(), b"Man");
+        assert_eq!(decode_base64("TWE=").unwrap(), b"Ma");
+        assert_eq!(decode_base64("TQ\n==").unwrap(), b"M");
+        assert_eq!(decode_base64("TQ").unwrap(), b"M");
+        assert!(decode_base64("T").is_err());
+        assert!(decode_base64("TW*u").is_err());
+        assert_eq!(encode_base64(b"Man"), "TWFu");
+        assert_eq!(encode_base64(b"Ma"), "TWE=");
+        assert_eq!(encode_base64(b"M"), "TQ==");
+    }
+}
@@ -0,0 +1,41 @@
+/// Bits per byte above which a payload most likely holds encrypted or
+/// compressed data.
+pub const HIGH_ENTROPY: f64 = 7.5;
+/// Fewest bytes whose entropy is reported. `n` bytes can reach at most
+/// log2(n) bits per byte, so frames shorter than 182 bytes could never pass
+/// `HIGH_ENTROPY`; small encrypted datagrams are left unjudged instead.
+pub const MIN_ENTROPY_SAMPLE: usize = 256;
+
+/// Shannon entropy of `bytes` in bits per byte, from 0 (one repeated value)
+/// to 8 (every byte value equally likely). Empty input has zero entropy.
+pub fn shannon_entropy(bytes: &[u8]) -> f64 {
+    if bytes.is_empty() {
+        return 0.0;
+    }
+    let mut counts = [0usize; 256];
+    for byte in bytes {
+        counts[*byte as usize] += 1;
+    }
+    let total = bytes.len() as f64;
+    counts
+        .iter()
+        .filter(|count| **count > 0)
+        .map(|count| {
+            let probability = *count as f64 / total;
+            -probability * probability.log2()
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn spans_zero_to_eight_bits() {
+        assert_eq!(shannon_entropy(&[]), 0.0);
+        assert_eq!(shannon_entropy(&[7; 64]), 0.0);
+        let all: Vec<u8> = (0..=255).collect();
+        assert!((shannon_entropy(&all) - 8.0).abs() < 1e-9);
+        assert!((shannon_entropy(b"abab") - 1.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,23 @@
+/// Summarizes an IPsec ESP packet (RFC 4303) from the SPI and sequence
+/// number that precede the encrypted payload, e.g. `ESP SPI=0x0000C0DE
+/// seq=7`. Payloads too short to hold both fields return `None`.
+pub fn parse_esp(payload: &[u8]) -> Option<String> {
+    let header = payload.get(..8)?;
+    let spi = u32::from_be_bytes([header[0], header[1], header[2], header[3]]);
+    let sequence = u32::from_be_bytes([header[4], header[5], header[6], header[7]]);
+    Some(format!("ESP SPI=0x{spi:08X} seq={sequence}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn reads_spi_and_sequence() {
+        let payload = [0, 0, 0xC0, 0xDE, 0, 0, 0, 7, 0xAA, 0xBB];
+        assert_eq!(
+            parse_esp(&payload).as_deref(),
+            Some("ESP SPI=0x0000C0DE seq=7")
+        );
+        assert!(parse_esp(&payload[..7]).is_none());
+    }
+}
@@ -0,0 +1,69 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::models::{DecodedLayers, Packet};
+
+type Endpoint = (String, u16);
+
+/// Direction-normalized 5-tuple: the protocol number and both endpoints,
+/// lower endpoint first so both directions of a conversation share a key.
+#[derive(PartialEq, Eq, Hash)]
+pub struct FlowKey {
+    protocol: u8,
+    low: Endpoint,
+    high: Endpoint,
+}
+
+impl FlowKey {
+    /// Extracts the key from an IP packet's layers. Ports are zero for
+    /// protocols without them; non-IP packets have no key.
+    pub fn from_layers(layers: &DecodedLayers) -> Option<FlowKey> {
+        let (protocol, source, destination) = match (&layers.ipv4, &layers.ipv6) {
+            (Some(ipv4), _) => (ipv4.protocol, &ipv4.source, &ipv4.destination),
+            (None, Some(ipv6)) => (ipv6.next_header, &ipv6.source, &ipv6.destination),
+            (None, None) => return None,
+        };
+        let (source_port, destination_port) = match (&layers.tcp, &layers.udp) {
+            (Some(tcp), _) => (tcp.source_port, tcp.destination_port),
+            (None, Some(udp)) => (udp.source_port, udp.destination_port),
+            (None, None) => (0, 0),
+        };
+        let a = (source.clone(), source_port);
+        let b = (destination.clone(), destination_port);
+        let (low, high) = if a <= b { (a, b) } else { (b, a) };
+        Some(FlowKey {
+            protocol,
+            low,
+            high,
+        })
+    }
+}
+
+/// Keeps only the first packet of each flow, annotating it with the flow's
+/// total packet count. Packets without a flow key are kept as they are.
+pub fn first_per_flow(packets: Vec<Packet>) -> Vec<Packet> {
+    let keys: Vec<Option<FlowKey>> = packets
+        .iter()
+        .map(|packet| packet.layers.as_ref().and_then(FlowKey::from_layers))
+        .collect();
+    let mut counts: HashMap<&FlowKey, usize> = HashMap::new();
+    for key in keys.iter().flatten() {
+        *counts.entry(key).or_default() += 1;
+    }
+    let mut seen = HashSet::new();
+    packets
+        .into_iter()
+        .zip(&keys)
+        .filter_map(|(mut packet, key)| {
+            let Some(key) = key else {
+                return Some(packet);
+            };
+            if !seen.insert(key) {
+                return None;
+            }
+            let note = format!(" [{} packets in flow]", counts[key]);
+            packet.info.summary.push_str(&note);
+            packet.info.info.push_str(&note);
+            Some(packet)
+        })
+        .collect()
+}
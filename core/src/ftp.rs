@@ -0,0 +1,93 @@
+use std::net::Ipv4Addr;
+
+use crate::text::{first_line, truncate_line};
+
+const COMMANDS: &[&str] = &[
+    "USER", "PASS", "ACCT", "CWD", "CDUP", "QUIT", "PORT", "EPRT", "PASV", "EPSV", "TYPE", "RETR",
+    "STOR", "APPE", "LIST", "NLST", "MLSD", "PWD", "MKD", "RMD", "DELE", "RNFR", "RNTO", "SIZE",
+    "MDTM", "REST", "ABOR", "SYST", "FEAT", "OPTS", "AUTH", "NOOP", "STAT", "HELP",
+];
+
+/// Summarizes an FTP control-channel command or reply. Passwords are masked,
+/// and passive-mode replies note the data channel they announce.
+pub fn parse_ftp(payload: &[u8]) -> Option<String> {
+    let line = first_line(payload)?;
+    let (verb, argument) = line.split_once(' ').unwrap_or((line, ""));
+    if COMMANDS.contains(&verb.to_ascii_uppercase().as_str()) {
+        if verb.eq_ignore_ascii_case("PASS") {
+            return Some("FTP PASS ****".to_string());
+        }
+        return Some(format!("FTP {}", truncate_line(line)));
+    }
+    let bytes = line.as_bytes();
+    let is_reply = bytes.len() >= 3
+        && bytes[..3].iter().all(u8::is_ascii_digit)
+        && matches!(bytes.get(3), None | Some(b' ' | b'-'));
+    if !is_reply {
+        return None;
+    }
+    let mut summary = format!("FTP {}", truncate_line(line));
+    let data_channel = match &line[..3] {
+        "227" => passive_address(argument),
+        "229" => extended_passive_port(argument).map(|port| format!("port {port}")),
+        _ => None,
+    };
+    if let Some(channel) = data_channel {
+        summary.push_str(&format!(" [data {channel}]"));
+    }
+    Some(summary)
+}
+
+/// Extracts `h1,h2,h3,h4,p1,p2` from a 227 reply as `address:port`.
+fn passive_address(text: &str) -> Option<String> {
+    let start = text.find(|ch: char| ch.is_ascii_digit())?;
+    let digits: Vec<u8> = text[start..]
+        .split(|ch: char| !ch.is_ascii_digit() && ch != ',')
+        .next()?
+        .split(',')
+        .map(str::parse)
+        .collect::<Result<_, _>>()
+        .ok()?;
+    let [a, b, c, d, high, low] = digits[..] else {
+        return None;
+    };
+    let port = u16::from_be_bytes([high, low]);
+    Some(format!("{}:{port}", Ipv4Addr::new(a, b, c, d)))
+}
+
+/// Extracts the port from a 229 reply such as `(|||6446|)`.
+fn extended_passive_port(text: &str) -> Option<u16> {
+    let start = text.find('(')? + 1;
+    let end = start + text[start..].find(')')?;
+    let inner = &text[start..end];
+    let delimiter = inner.chars().next()?;
+    inner.split(delimiter).nth(3)?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn summarizes_commands_and_masks_passwords() {
+        assert_eq!(
+            parse_ftp(b"RETR report.pdf\r\n").as_deref(),
+            Some("FTP RETR report.pdf")
+        );
+        assert_eq!(
+            parse_ftp(b"PASS hunter2\r\n").as_deref(),
+            Some("FTP PASS ****")
+        );
+        assert!(parse_ftp(b"GET / HTTP/1.1\r\n").is_none());
+    }
+    #[test]
+    fn notes_passive_data_channel() {
+        assert_eq!(
+            parse_ftp(b"227 Entering Passive Mode (192,168,1,2,195,80).\r\n").as_deref(),
+            Some("FTP 227 Entering Passive Mode (192,168,1,2,195,80). [data 192.168.1.2:50000]")
+        );
+        assert_eq!(
+            parse_ftp(b"229 Entering Extended Passive Mode (|||6446|)\r\n").as_deref(),
+            Some("FTP 229 Entering Extended Passive Mode (|||6446|) [data port 6446]")
+        );
+    }
+}
@@ -0,0 +1,43 @@
+use std::collections::HashMap;
+
+use crate::models::{Packet, PacketProcessingResult};
+
+/// Appends a location tag such as `(US)` to each packet's source and
+/// destination, using the IP addresses from its decoded network layer.
+/// `lookup` is consulted once per distinct address; addresses it cannot
+/// resolve are left unannotated.
+pub fn annotate_locations(
+    result: &mut PacketProcessingResult,
+    mut lookup: impl FnMut(&str) -> Option<String>,
+) {
+    let mut cache: HashMap<String, Option<String>> = HashMap::new();
+    for packet in &mut result.packets {
+        let Some((source_ip, destination_ip)) = network_addresses(packet) else {
+            continue;
+        };
+        let mut resolve = |ip: &str| {
+            cache
+                .entry(ip.to_string())
+                .or_insert_with(|| lookup(ip).filter(|tag| !tag.is_empty()))
+                .clone()
+        };
+        if let Some(tag) = resolve(&source_ip) {
+            packet.source = format!("{} ({tag})", packet.source);
+        }
+        if let Some(tag) = resolve(&destination_ip) {
+            packet.destination = format!("{} ({tag})", packet.destination);
+        }
+    }
+}
+
+/// IP addresses of a packet's decoded network layer, source first.
+pub fn network_addresses(packet: &Packet) -> Option<(String, String)> {
+    let layers = packet.layers.as_ref()?;
+    if let Some(ipv4) = &layers.ipv4 {
+        return Some((ipv4.source.clone(), ipv4.destination.clone()));
+    }
+    layers
+        .ipv6
+        .as_ref()
+        .map(|ipv6| (ipv6.source.clone(), ipv6.destination.clone()))
+}
@@ -0,0 +1,83 @@
+/// Fields of a GRE header (RFC 2784/2890, plus the enhanced version 1 header
+/// used by PPTP) and the payload it carries.
+pub struct GreHeader<'a> {
+    pub protocol_type: u16,
+    pub key: Option<u32>,
+    /// PPTP call ID, present only in version 1 headers.
+    pub call_id: Option<u16>,
+    pub payload: &'a [u8],
+}
+
+/// Parses a GRE header, skipping the optional checksum, sequence and
+/// acknowledgment fields its flags announce. Source-routed GRE is rejected.
+pub fn parse_gre(packet: &[u8]) -> Option<GreHeader<'_>> {
+    let flags = *packet.first()?;
+    let version_byte = *packet.get(1)?;
+    if flags & 0x40 != 0 {
+        return None;
+    }
+    let version = version_byte & 0x07;
+    match version {
+        0 => {}
+        // Enhanced GRE never carries a checksum and always carries the
+        // payload length and call ID in place of the key.
+        1 if flags & 0xA0 == 0x20 => {}
+        _ => return None,
+    }
+    let protocol_type = u16::from_be_bytes([*packet.get(2)?, *packet.get(3)?]);
+    let read_u32 = |offset: usize| {
+        let bytes = packet.get(offset..offset + 4)?;
+        Some(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    };
+    let mut offset = 4;
+    if flags & 0x80 != 0 {
+        offset += 4;
+    }
+    let key = if flags & 0x20 != 0 {
+        offset += 4;
+        Some(read_u32(offset - 4)?)
+    } else {
+        None
+    };
+    if flags & 0x10 != 0 {
+        offset += 4;
+    }
+    if version == 1 && version_byte & 0x80 != 0 {
+        offset += 4;
+    }
+    let (key, call_id) = match version {
+        1 => (None, key.map(|key| key as u16)),
+        _ => (key, None),
+    };
+    Some(GreHeader {
+        protocol_type,
+        key,
+        call_id,
+        payload: packet.get(offset..)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn skips_optional_fields() {
+        let packet = [0x30, 0, 0x08, 0, 0, 0, 0, 7, 0, 0, 0, 9, 0x45];
+        let gre = parse_gre(&packet).unwrap();
+        assert_eq!(gre.protocol_type, 0x0800);
+        assert_eq!(gre.key, Some(7));
+        assert_eq!(gre.payload, [0x45]);
+        assert!(parse_gre(&[0x20, 0, 0x08, 0, 0, 0]).is_none());
+    }
+    #[test]
+    fn reads_pptp_call_id() {
+        let packet = [
+            0x30, 0x81, 0x88, 0x0B, 0, 1, 0x12, 0x34, 0, 0, 0, 5, 0, 0, 0, 4, 0x21,
+        ];
+        let gre = parse_gre(&packet).unwrap();
+        assert_eq!(gre.call_id, Some(0x1234));
+        assert!(gre.key.is_none());
+        assert_eq!(gre.payload, [0x21]);
+        assert!(parse_gre(&[0x80, 0x01, 0x88, 0x0B, 0, 0, 0, 0]).is_none());
+    }
+}
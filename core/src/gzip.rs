@@ -0,0 +1,48 @@
+use std::io::Read;
+
+use flate2::read::MultiGzDecoder;
+
+const GZIP_MAGIC: [u8; 2] = [0x1F, 0x8B];
+/// Largest decompressed capture accepted, so a tiny upload cannot expand
+/// into gigabytes of memory.
+const MAX_DECOMPRESSED: u64 = 1 << 30;
+
+pub fn is_gzip(data: &[u8]) -> bool {
+    data.starts_with(&GZIP_MAGIC)
+}
+
+/// Inflates a gzip file, including ones made of several concatenated
+/// members as produced by `cat a.gz b.gz`.
+pub fn gunzip(data: &[u8]) -> Result<Vec<u8>, String> {
+    let mut inflated = Vec::new();
+    MultiGzDecoder::new(data)
+        .take(MAX_DECOMPRESSED + 1)
+        .read_to_end(&mut inflated)
+        .map_err(|err| format!("Gzip decompression failed: {err}"))?;
+    if inflated.len() as u64 > MAX_DECOMPRESSED {
+        return Err(format!(
+            "Gzip data expands past {} MiB",
+            MAX_DECOMPRESSED >> 20
+        ));
+    }
+    Ok(inflated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    use flate2::Compression;
+    use flate2::write::GzEncoder;
+
+    #[test]
+    fn round_trips_and_rejects_corrupt_streams() {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(b"capture bytes").unwrap();
+        let compressed = encoder.finish().unwrap();
+        assert!(is_gzip(&compressed));
+        assert_eq!(gunzip(&compressed).unwrap(), b"capture bytes");
+        assert!(gunzip(&compressed[..compressed.len() / 2]).is_err());
+    }
+}
@@ -0,0 +1,339 @@
+use std::collections::HashMap;
+
+use crate::encoding::encode_base64;
+use crate::models::{HttpObject, Packet};
+
+/// Bytes of TCP payload kept per flow direction; later data is dropped and
+/// objects reaching past it are marked truncated.
+const MAX_STREAM_BYTES: usize = 16 * 1024 * 1024;
+const METHODS: [&str; 9] = [
+    "GET", "POST", "PUT", "DELETE", "HEAD", "OPTIONS", "PATCH", "CONNECT", "TRACE",
+];
+
+type Endpoint = (String, u16);
+
+/// TCP payload of one flow direction, keyed by sequence number until the
+/// capture has been read.
+#[derive(Default)]
+struct Stream {
+    segments: Vec<(u32, Vec<u8>)>,
+    stored: usize,
+    overflowed: bool,
+}
+
+impl Stream {
+    fn push(&mut self, sequence: u32, data: &[u8]) {
+        if self.stored + data.len() > MAX_STREAM_BYTES {
+            self.overflowed = true;
+            return;
+        }
+        self.stored += data.len();
+        self.segments.push((sequence, data.to_vec()));
+    }
+
+    /// Orders segments by sequence number and joins them, skipping
+    /// retransmitted bytes. Stops at the first gap, reporting it like an
+    /// overflow.
+    fn assemble(mut self) -> (Vec<u8>, bool) {
+        let Some(&(first, _)) = self.segments.first() else {
+            return (Vec::new(), self.overflowed);
+        };
+        // Segments may arrive out of order, so the stream starts at the
+        // earliest sequence number, comparing around the wrap point.
+        let earliest = self
+            .segments
+            .iter()
+            .map(|(sequence, _)| sequence.wrapping_sub(first) as i32)
+            .min()
+            .unwrap_or(0);
+        let base = first.wrapping_add(earliest as u32);
+        self.segments
+            .sort_by_key(|(sequence, _)| sequence.wrapping_sub(base));
+        let mut bytes = Vec::with_capacity(self.stored);
+        let mut truncated = self.overflowed;
+        for (sequence, data) in self.segments {
+            let start = sequence.wrapping_sub(base) as usize;
+            if start > bytes.len() {
+                truncated = true;
+                break;
+            }
+            if let Some(fresh) = data.get(bytes.len() - start..) {
+                bytes.extend_from_slice(fresh);
+            }
+        }
+        (bytes, truncated)
+    }
+}
+
+/// Reassembles every TCP flow in `packets` and pairs HTTP/1.x responses
+/// with the requests sent the other way, returning one object per
+/// response in order of each connection's first segment.
+pub fn extract_objects(packets: &[Packet], include_bodies: bool) -> Vec<HttpObject> {
+    let mut order = Vec::new();
+    let mut streams: HashMap<(Endpoint, Endpoint), Stream> = HashMap::new();
+    for packet in packets {
+        let Some((from, to, sequence, data)) = tcp_payload(packet) else {
+            continue;
+        };
+        let key = (from, to);
+        if !streams.contains_key(&key) {
+            order.push(key.clone());
+        }
+        streams.entry(key).or_default().push(sequence, data);
+    }
+    let assembled: HashMap<(Endpoint, Endpoint), (Vec<u8>, bool)> = streams
+        .into_iter()
+        .map(|(key, stream)| (key, stream.assemble()))
+        .collect();
+    let mut objects = Vec::new();
+    for (server, client) in order {
+        let Some(&(ref responses, responses_truncated)) =
+            assembled.get(&(server.clone(), client.clone()))
+        else {
+            continue;
+        };
+        if !responses.starts_with(b"HTTP/") {
+            continue;
+        }
+        let requests = assembled
+            .get(&(client.clone(), server.clone()))
+            .map(|(bytes, _)| parse_requests(bytes))
+            .unwrap_or_default();
+        let mut requests = requests.into_iter();
+        let mut offset = 0;
+        while let Some(head) = parse_head(&responses[offset..]) {
+            let status = head
+                .start_line
+                .split(' ')
+                .nth(1)
+                .and_then(|code| code.parse::<u16>().ok())
+                .unwrap_or(0);
+            let body_start = offset + head.length;
+            // Interim responses such as `100 Continue` precede the final
+            // response to the same request and carry no body.
+            if (100..200).contains(&status) {
+                offset = body_start;
+                continue;
+            }
+            let request = requests.next();
+            let method = request.as_ref().map(|(method, _)| method.as_str());
+            let (body, consumed, complete) =
+                if method == Some("HEAD") || status == 204 || status == 304 {
+                    (Vec::new(), 0, true)
+                } else {
+                    read_body(&head, &responses[body_start..], true)
+                };
+            offset = body_start + consumed;
+            objects.push(HttpObject {
+                client: format_endpoint(&client),
+                server: format_endpoint(&server),
+                method: method.map(str::to_string),
+                uri: request.map(|(_, uri)| uri),
+                status,
+                content_type: head.header("content-type").map(str::to_string),
+                body_length: body.len(),
+                body: include_bodies.then(|| encode_base64(&body)),
+                truncated: !complete || (responses_truncated && offset == responses.len()),
+            });
+            if !complete {
+                break;
+            }
+        }
+    }
+    objects
+}
+
+/// Returns the directional endpoints, sequence number and TCP payload of a
+/// packet, or `None` for anything but a TCP segment carrying data. Segments
+/// rebuilt from IPv4 fragments are skipped, as `payload` holds only the last
+/// fragment.
+fn tcp_payload(packet: &Packet) -> Option<(Endpoint, Endpoint, u32, &[u8])> {
+    let layers = packet.layers.as_ref()?;
+    let tcp = layers.tcp.as_ref()?;
+    let l3 = packet.l3_offset?;
+    let (source, destination, ip_payload) = match (&layers.ipv4, &layers.ipv6) {
+        (Some(ipv4), _) if ipv4.protocol == 6 => (
+            &ipv4.source,
+            &ipv4.destination,
+            packet
+                .payload
+                .get(l3 + ipv4.header_length..l3 + ipv4.total_length)?,
+        ),
+        (None, Some(ipv6)) if ipv6.next_header == 6 => (
+            &ipv6.source,
+            &ipv6.destination,
+            packet.payload.get(l3 + 40..l3 + 40 + ipv6.payload_length)?,
+        ),
+        _ => return None,
+    };
+    let header_len = usize::from(ip_payload.get(12)? >> 4) * 4;
+    let data = ip_payload
+        .get(header_len..)
+        .filter(|data| !data.is_empty())?;
+    Some((
+        (source.clone(), tcp.source_port),
+        (destination.clone(), tcp.destination_port),
+        tcp.sequence?,
+        data,
+    ))
+}
+
+fn format_endpoint((address, port): &Endpoint) -> String {
+    if address.contains(':') {
+        format!("[{address}]:{port}")
+    } else {
+        format!("{address}:{port}")
+    }
+}
+
+struct Head {
+    start_line: String,
+    headers: Vec<(String, String)>,
+    /// Bytes up to and including the blank line ending the header block.
+    length: usize,
+}
+
+impl Head {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+fn parse_head(data: &[u8]) -> Option<Head> {
+    let end = data.windows(4).position(|window| window == b"\r\n\r\n")?;
+    let text = String::from_utf8_lossy(&data[..end]);
+    let mut lines = text.split("\r\n");
+    let start_line = lines.next()?.to_string();
+    let headers = lines
+        .filter_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            Some((name.trim().to_string(), value.trim().to_string()))
+        })
+        .collect();
+    Some(Head {
+        start_line,
+        headers,
+        length: end + 4,
+    })
+}
+
+/// Splits a client stream into (method, URI) pairs, skipping request
+/// bodies.
+fn parse_requests(data: &[u8]) -> Vec<(String, String)> {
+    let mut requests = Vec::new();
+    let mut offset = 0;
+    while let Some(head) = parse_head(&data[offset..]) {
+        let mut parts = head.start_line.split(' ');
+        let (Some(method), Some(uri)) = (parts.next(), parts.next()) else {
+            break;
+        };
+        if !METHODS.contains(&method) {
+            break;
+        }
+        requests.push((method.to_string(), uri.to_string()));
+        let body_start = offset + head.length;
+        let (_, consumed, complete) = read_body(&head, &data[body_start..], false);
+        if !complete {
+            break;
+        }
+        offset = body_start + consumed;
+    }
+    requests
+}
+
+/// Reads a message body framed by chunked encoding, Content-Length, or (for
+/// responses only) the end of the connection. Returns the decoded body, the
+/// bytes consumed, and whether the body was complete.
+fn read_body(head: &Head, data: &[u8], until_close: bool) -> (Vec<u8>, usize, bool) {
+    let chunked = head
+        .header("transfer-encoding")
+        .is_some_and(|value| value.to_ascii_lowercase().contains("chunked"));
+    if chunked {
+        return decode_chunked(data);
+    }
+    match head.header("content-length") {
+        Some(length) => {
+            let length = length.parse::<usize>().unwrap_or(0);
+            let available = length.min(data.len());
+            (data[..available].to_vec(), available, available == length)
+        }
+        None if until_close => (data.to_vec(), data.len(), true),
+        None => (Vec::new(), 0, true),
+    }
+}
+
+fn decode_chunked(data: &[u8]) -> (Vec<u8>, usize, bool) {
+    let mut body = Vec::new();
+    let mut offset = 0;
+    loop {
+        let Some(line_end) = find_crlf(&data[offset..]) else {
+            return (body, data.len(), false);
+        };
+        let line = String::from_utf8_lossy(&data[offset..offset + line_end]);
+        let size = line.split(';').next().unwrap_or("").trim();
+        let Ok(size) = usize::from_str_radix(size, 16) else {
+            return (body, data.len(), false);
+        };
+        offset += line_end + 2;
+        if size == 0 {
+            // Skip any trailer fields up to the terminating blank line.
+            while let Some(line_end) = find_crlf(&data[offset..]) {
+                offset += line_end + 2;
+                if line_end == 0 {
+                    return (body, offset, true);
+                }
+            }
+            return (body, data.len(), false);
+        }
+        // The size comes from the wire, so guard the arithmetic as well as
+        // the slice.
+        let end = offset.checked_add(size);
+        let Some(chunk) = end.and_then(|end| data.get(offset..end)) else {
+            body.extend_from_slice(&data[offset..]);
+            return (body, data.len(), false);
+        };
+        body.extend_from_slice(chunk);
+        offset = end
+            .and_then(|end| end.checked_add(2))
+            .map_or(data.len(), |next| next.min(data.len()));
+    }
+}
+
+fn find_crlf(data: &[u8]) -> Option<usize> {
+    data.windows(2).position(|window| window == b"\r\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn decodes_chunked_bodies_with_trailers() {
+        let data = b"4\r\nWiki\r\n5;ext=1\r\npedia\r\n0\r\nX-Trailer: 1\r\n\r\nNEXT";
+        let (body, consumed, complete) = decode_chunked(data);
+        assert_eq!(body, b"Wikipedia");
+        assert_eq!(&data[consumed..], b"NEXT");
+        assert!(complete);
+        let (body, _, complete) = decode_chunked(b"9\r\nWiki");
+        assert_eq!(body, b"Wiki");
+        assert!(!complete);
+        let (body, _, complete) = decode_chunked(b"ffffffffffffffff\r\nWiki");
+        assert_eq!(body, b"Wiki");
+        assert!(!complete);
+    }
+    #[test]
+    fn joins_out_of_order_and_retransmitted_segments() {
+        let mut stream = Stream::default();
+        stream.push(103, b"def");
+        stream.push(106, b"ghi");
+        stream.push(100, b"abc");
+        stream.push(103, b"def");
+        assert_eq!(stream.assemble(), (b"abcdefghi".to_vec(), false));
+        let mut gapped = Stream::default();
+        gapped.push(0, b"ab");
+        gapped.push(5, b"cd");
+        assert_eq!(gapped.assemble(), (b"ab".to_vec(), true));
+    }
+}
@@ -0,0 +1,140 @@
+const PREFACE: &[u8] = b"PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n";
+const FRAME_HEADER_LEN: usize = 9;
+const MAX_DEFAULT_FRAME: usize = 16_384;
+
+/// Recognizes cleartext HTTP/2 (h2c): either the client connection preface
+/// or a segment made up entirely of well-formed frames.
+pub fn parse_http2(payload: &[u8]) -> Option<String> {
+    let (frames, has_preface) = match payload.strip_prefix(PREFACE) {
+        Some(rest) => (rest, true),
+        None => (payload, false),
+    };
+    let mut offset = 0;
+    let mut types = Vec::new();
+    while offset + FRAME_HEADER_LEN <= frames.len() {
+        let header = &frames[offset..offset + FRAME_HEADER_LEN];
+        let length = u32::from_be_bytes([0, header[0], header[1], header[2]]) as usize;
+        let stream = u32::from_be_bytes([header[5], header[6], header[7], header[8]]);
+        let Some(name) = frame_name(header[3]) else {
+            break;
+        };
+        if length > MAX_DEFAULT_FRAME || !plausible_stream(header[3], stream) {
+            break;
+        }
+        let start = offset + FRAME_HEADER_LEN;
+        let Some(body) = frames.get(start..start + length) else {
+            break;
+        };
+        if !plausible_frame(header[3], header[4], body) {
+            break;
+        }
+        if !types.contains(&name) {
+            types.push(name);
+        }
+        offset += FRAME_HEADER_LEN + length;
+    }
+    if has_preface {
+        if types.is_empty() {
+            return Some("HTTP/2 connection preface".to_string());
+        }
+        return Some(format!("HTTP/2 connection preface, {}", types.join(", ")));
+    }
+    // Without the preface, only accept segments made entirely of frames.
+    (!types.is_empty() && offset == frames.len()).then(|| format!("HTTP/2 {}", types.join(", ")))
+}
+
+fn frame_name(frame_type: u8) -> Option<&'static str> {
+    Some(match frame_type {
+        0x0 => "DATA",
+        0x1 => "HEADERS",
+        0x2 => "PRIORITY",
+        0x3 => "RST_STREAM",
+        0x4 => "SETTINGS",
+        0x5 => "PUSH_PROMISE",
+        0x6 => "PING",
+        0x7 => "GOAWAY",
+        0x8 => "WINDOW_UPDATE",
+        0x9 => "CONTINUATION",
+        _ => return None,
+    })
+}
+
+/// Connection-level frames use stream 0 and stream frames never do; the
+/// reserved high bit must be clear.
+fn plausible_stream(frame_type: u8, stream: u32) -> bool {
+    if stream & 0x8000_0000 != 0 {
+        return false;
+    }
+    match frame_type {
+        0x4 | 0x6 | 0x7 => stream == 0,
+        0x8 => true,
+        _ => stream != 0,
+    }
+}
+
+/// Checks the flags and payload size each frame type defines (RFC 9113
+/// section 6), so arbitrary TCP payloads are not mistaken for frames.
+fn plausible_frame(frame_type: u8, flags: u8, body: &[u8]) -> bool {
+    let defined_flags = match frame_type {
+        0x0 => 0x09,
+        0x1 => 0x2D,
+        0x4 | 0x6 => 0x01,
+        0x5 => 0x0C,
+        0x9 => 0x04,
+        _ => 0x00,
+    };
+    if flags & !defined_flags != 0 {
+        return false;
+    }
+    match frame_type {
+        0x2 => body.len() == 5,
+        0x3 => body.len() == 4,
+        0x4 if flags & 0x01 != 0 => body.is_empty(),
+        0x4 => body.len().is_multiple_of(6),
+        0x6 => body.len() == 8,
+        0x7 => body.len() >= 8,
+        // A zero window increment is a protocol error.
+        0x8 => {
+            body.len() == 4
+                && u32::from_be_bytes([body[0], body[1], body[2], body[3]]) & 0x7FFF_FFFF != 0
+        }
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn recognizes_preface_and_settings() {
+        let mut payload = PREFACE.to_vec();
+        payload.extend_from_slice(&[0, 0, 0, 4, 0, 0, 0, 0, 0]);
+        assert_eq!(
+            parse_http2(&payload).as_deref(),
+            Some("HTTP/2 connection preface, SETTINGS")
+        );
+    }
+    #[test]
+    fn requires_whole_frames_without_preface() {
+        let headers = [0, 0, 1, 1, 4, 0, 0, 0, 1, 0x82];
+        assert_eq!(parse_http2(&headers).as_deref(), Some("HTTP/2 HEADERS"));
+        assert!(parse_http2(&headers[..9]).is_none());
+        assert!(parse_http2(b"GET / HTTP/1.1\r\n\r\n").is_none());
+    }
+    #[test]
+    fn rejects_frames_with_undefined_flags_or_sizes() {
+        let window_update = [0, 0, 4, 8, 0, 0, 0, 0, 1, 0, 0, 0x10, 0];
+        assert_eq!(
+            parse_http2(&window_update).as_deref(),
+            Some("HTTP/2 WINDOW_UPDATE")
+        );
+        let mut zero_increment = window_update;
+        zero_increment[12] = 0;
+        zero_increment[11] = 0;
+        assert!(parse_http2(&zero_increment).is_none());
+        let mut flagged = window_update;
+        flagged[4] = 0x01;
+        assert!(parse_http2(&flagged).is_none());
+        assert!(parse_http2(&[0, 0, 3, 8, 0, 0, 0, 0, 1, 0, 0, 1]).is_none());
+    }
+}
@@ -0,0 +1,89 @@
+use std::net::Ipv4Addr;
+
+const V3_REPORT: u8 = 0x22;
+/// Group records described per report; the rest are counted.
+const MAX_RECORDS: usize = 8;
+
+/// Summarizes an IGMPv3 membership report (RFC 3376) from its group
+/// records, e.g. `IGMPv3 report: join 239.1.1.1 (2 sources)`. Other IGMP
+/// messages and malformed reports return `None`.
+pub fn parse_igmp(payload: &[u8]) -> Option<String> {
+    if payload.len() < 8 || payload[0] != V3_REPORT {
+        return None;
+    }
+    let count = u16::from_be_bytes([payload[6], payload[7]]) as usize;
+    let mut records = Vec::new();
+    let mut offset = 8;
+    for _ in 0..count {
+        let header = payload.get(offset..offset + 8)?;
+        let sources = u16::from_be_bytes([header[2], header[3]]) as usize;
+        let aux_len = header[1] as usize * 4;
+        let group = Ipv4Addr::new(header[4], header[5], header[6], header[7]);
+        offset += 8 + sources * 4 + aux_len;
+        if offset > payload.len() {
+            return None;
+        }
+        if records.len() < MAX_RECORDS {
+            let action = record_action(header[0], sources)?;
+            records.push(match sources {
+                0 => format!("{action} {group}"),
+                1 => format!("{action} {group} (1 source)"),
+                _ => format!("{action} {group} ({sources} sources)"),
+            });
+        }
+    }
+    if records.is_empty() {
+        return Some("IGMPv3 report: no group records".to_string());
+    }
+    let mut summary = format!("IGMPv3 report: {}", records.join(", "));
+    if count > MAX_RECORDS {
+        summary.push_str(&format!(" (+{} more)", count - MAX_RECORDS));
+    }
+    Some(summary)
+}
+
+/// Describes a group record type. Changing to exclude mode with no sources
+/// is an any-source join, and to include mode with none is a leave.
+fn record_action(record_type: u8, sources: usize) -> Option<&'static str> {
+    let action = match (record_type, sources) {
+        (3, 0) | (6, _) => "leave",
+        (4, 0) | (5, _) => "join",
+        (1, _) | (3, _) => "include",
+        (2, _) | (4, _) => "exclude",
+        _ => return None,
+    };
+    Some(action)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(records: &[&[u8]], count: u16) -> Vec<u8> {
+        let mut payload = vec![V3_REPORT, 0, 0, 0, 0, 0];
+        payload.extend_from_slice(&count.to_be_bytes());
+        for record in records {
+            payload.extend_from_slice(record);
+        }
+        payload
+    }
+
+    #[test]
+    fn describes_group_records() {
+        let ssm = [5, 0, 0, 2, 239, 1, 1, 1, 10, 0, 0, 1, 10, 0, 0, 2];
+        let leave = [3, 0, 0, 0, 239, 2, 2, 2];
+        assert_eq!(
+            parse_igmp(&report(&[&ssm, &leave], 2)).as_deref(),
+            Some("IGMPv3 report: join 239.1.1.1 (2 sources), leave 239.2.2.2")
+        );
+    }
+
+    #[test]
+    fn rejects_records_past_payload() {
+        let short = [4, 0, 0, 3, 239, 1, 1, 1, 10, 0, 0, 1];
+        assert!(parse_igmp(&report(&[&short], 1)).is_none());
+        // The count claims a record that is not there.
+        assert!(parse_igmp(&report(&[], 1)).is_none());
+        assert!(parse_igmp(&[0x16, 0, 0, 0, 239, 1, 1, 1]).is_none());
+    }
+}
@@ -0,0 +1,106 @@
+/// Iterates the type/value pairs of a Hop-by-Hop or Destination Options
+/// header body, skipping Pad1 and stopping at the first option whose length
+/// overruns the header.
+pub fn ipv6_options(area: &[u8]) -> Vec<(u8, &[u8])> {
+    let mut options = Vec::new();
+    let mut offset = 0;
+    while offset < area.len() {
+        let option_type = area[offset];
+        if option_type == 0 {
+            offset += 1;
+            continue;
+        }
+        let Some(&length) = area.get(offset + 1) else {
+            break;
+        };
+        let end = offset + 2 + length as usize;
+        if end > area.len() {
+            break;
+        }
+        options.push((option_type, &area[offset + 2..end]));
+        offset = end;
+    }
+    options
+}
+
+/// Checks that the options in a Hop-by-Hop or Destination Options header
+/// body, padding included, exactly fill it. A final option that claims more
+/// bytes than remain means the header was mis-built or crafted.
+pub fn options_fill_header(area: &[u8]) -> bool {
+    let mut offset = 0;
+    while offset < area.len() {
+        if area[offset] == 0 {
+            offset += 1;
+            continue;
+        }
+        let Some(&length) = area.get(offset + 1) else {
+            return false;
+        };
+        offset += 2 + length as usize;
+    }
+    offset == area.len()
+}
+
+/// Returns the Tunnel Encapsulation Limit (RFC 2473) carried in a
+/// Destination Options header body, if any.
+pub fn tunnel_encapsulation_limit(area: &[u8]) -> Option<u8> {
+    ipv6_options(area)
+        .into_iter()
+        .find(|(option_type, value)| *option_type == 4 && value.len() == 1)
+        .map(|(_, value)| value[0])
+}
+
+/// Returns the value of a Router Alert option (RFC 2711) carried in a
+/// Hop-by-Hop Options header body, if any. Zero signals MLD.
+pub fn router_alert(area: &[u8]) -> Option<u16> {
+    ipv6_options(area)
+        .into_iter()
+        .find(|(option_type, value)| *option_type == 5 && value.len() == 2)
+        .map(|(_, value)| u16::from_be_bytes([value[0], value[1]]))
+}
+
+/// Checks an extension header chain against RFC 8200 §4.1: Hop-by-Hop
+/// Options only first, Destination Options at most twice, and every other
+/// header at most once.
+pub fn extension_order_valid(chain: &[u8]) -> bool {
+    if chain.iter().skip(1).any(|&header| header == 0) {
+        return false;
+    }
+    chain.iter().enumerate().all(|(index, header)| {
+        let repeats = chain[..index].iter().filter(|&seen| seen == header).count();
+        repeats == 0 || (*header == 60 && repeats == 1)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn finds_tunnel_limit_between_padding() {
+        let area = [0, 4, 1, 3, 1, 0];
+        assert_eq!(tunnel_encapsulation_limit(&area), Some(3));
+    }
+    #[test]
+    fn finds_mld_router_alert() {
+        assert_eq!(router_alert(&[5, 2, 0, 0, 1, 0]), Some(0));
+        assert_eq!(router_alert(&[5, 1, 0, 1, 1, 0]), None);
+    }
+    #[test]
+    fn validates_extension_order() {
+        assert!(extension_order_valid(&[0, 60, 43, 44, 60]));
+        assert!(!extension_order_valid(&[60, 0]));
+        assert!(!extension_order_valid(&[43, 43]));
+        assert!(!extension_order_valid(&[60, 60, 60]));
+    }
+    #[test]
+    fn detects_options_overrunning_header() {
+        assert!(options_fill_header(&[5, 2, 0, 0, 1, 0]));
+        assert!(options_fill_header(&[0, 0, 0, 0, 0, 0]));
+        assert!(!options_fill_header(&[5, 2, 0, 0, 1, 1]));
+        assert!(!options_fill_header(&[0, 0, 0, 0, 0, 1]));
+    }
+    #[test]
+    fn stops_at_overlong_option() {
+        assert!(ipv6_options(&[1, 9, 0, 0]).is_empty());
+    }
+}
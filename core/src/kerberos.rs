@@ -0,0 +1,73 @@
+/// Identifies the Kerberos message type from its ASN.1 application tag.
+/// `framed` strips the 4-byte record mark used over TCP.
+pub fn parse_kerberos(payload: &[u8], framed: bool) -> Option<String> {
+    let message = if framed {
+        let record_len = u32::from_be_bytes(payload.get(..4)?.try_into().ok()?);
+        if record_len & 0x8000_0000 != 0 {
+            return None;
+        }
+        &payload[4..]
+    } else {
+        payload
+    };
+    let name = match *message.first()? {
+        0x6A => "AS-REQ",
+        0x6B => "AS-REP",
+        0x6C => "TGS-REQ",
+        0x6D => "TGS-REP",
+        0x6E => "AP-REQ",
+        0x6F => "AP-REP",
+        0x74 => "KRB-SAFE",
+        0x75 => "KRB-PRIV",
+        0x76 => "KRB-CRED",
+        0x7E => "KRB-ERROR",
+        _ => return None,
+    };
+    let (length, header_len) = read_der_length(&message[1..])?;
+    let body = &message[1 + header_len..];
+    // Every message body is a SEQUENCE whose length fits the outer element.
+    if body.first() != Some(&0x30) || length > body.len() {
+        return None;
+    }
+    Some(format!("Kerberos {name}"))
+}
+
+/// Decodes a DER length, returning the length and the bytes it occupied.
+fn read_der_length(data: &[u8]) -> Option<(usize, usize)> {
+    let first = *data.first()?;
+    if first & 0x80 == 0 {
+        return Some((first as usize, 1));
+    }
+    let count = (first & 0x7F) as usize;
+    if count == 0 || count > 4 || data.len() < 1 + count {
+        return None;
+    }
+    let length = data[1..=count]
+        .iter()
+        .fold(0usize, |acc, &byte| (acc << 8) | byte as usize);
+    Some((length, 1 + count))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn recognizes_udp_and_tcp_framing() {
+        let as_req = [0x6A, 0x81, 0x03, 0x30, 0x01, 0x00];
+        assert_eq!(
+            parse_kerberos(&as_req, false).as_deref(),
+            Some("Kerberos AS-REQ")
+        );
+        let mut framed = vec![0, 0, 0, 6];
+        framed.extend_from_slice(&[0x7E, 0x02, 0x30, 0x00]);
+        assert_eq!(
+            parse_kerberos(&framed, true).as_deref(),
+            Some("Kerberos KRB-ERROR")
+        );
+    }
+    #[test]
+    fn rejects_lengths_past_payload() {
+        assert!(parse_kerberos(&[0x6C, 0x84, 0xFF, 0xFF, 0xFF, 0xFF, 0x30], false).is_none());
+        assert!(parse_kerberos(&[0x6C, 0x82, 0x01], false).is_none());
+    }
+}
@@ -1,158 +1,290 @@
+use std::collections::{BTreeMap, HashMap};
 use std::convert::TryInto;
 use std::net::{Ipv4Addr, Ipv6Addr};
+use std::sync::LazyLock;
 
+use js_sys::{Function, Uint8Array};
 use pcap_parser::{
     PcapError, PcapNGSlice, nom,
     pcapng::{Block, InterfaceDescriptionBlock},
     traits::PcapNGPacketBlock,
 };
 use serde::Serialize;
+use sha2::{Digest, Sha256};
 use wasm_bindgen::prelude::*;
+mod arp;
+mod capwap;
 mod core_format;
 mod decode;
+mod dhcp;
+mod dns;
+mod dtls;
+mod encoding;
+mod entropy;
+mod esp;
+mod flow;
+mod ftp;
+mod geo;
+mod gre;
+mod gzip;
+mod http;
+mod http2;
+mod igmp;
+mod ipv6_ext;
+mod kerberos;
+mod linktype;
+mod mail;
 mod models;
+mod mpls;
+mod names;
+mod nbns;
+mod ndp;
+mod options;
+mod ospf;
 mod pcap;
 mod pcapng;
+mod ppp;
 mod preview;
+mod ptp;
+mod reassembly;
+mod sctp;
+mod services;
+mod sip;
+mod sll;
+mod stun;
+mod syslog;
+mod tcp;
+mod text;
+mod tls;
 
-use crate::core_format::{CaptureFormat, detect_format};
-use crate::decode::build_summary_from_layers;
+use crate::arp::ArpConflictTracker;
+use crate::capwap::parse_capwap;
+pub use crate::core_format::CaptureFormat;
+use crate::core_format::detect_format;
+use crate::decode::{build_summary_from_layers, protocol_stack};
+use crate::dhcp::parse_dhcp;
+use crate::dns::{describe_dns, parse_dns};
+use crate::dtls::parse_dtls;
+use crate::encoding::{decode_base64, decode_hex};
+use crate::entropy::{HIGH_ENTROPY, MIN_ENTROPY_SAMPLE, shannon_entropy};
+use crate::esp::parse_esp;
+use crate::flow::first_per_flow;
+use crate::ftp::parse_ftp;
+use crate::geo::annotate_locations;
+use crate::gre::parse_gre;
+use crate::gzip::{gunzip, is_gzip};
+use crate::http::extract_objects;
+use crate::http2::parse_http2;
+use crate::igmp::parse_igmp;
+use crate::ipv6_ext::{
+    extension_order_valid, options_fill_header, router_alert, tunnel_encapsulation_limit,
+};
+use crate::kerberos::parse_kerberos;
+use crate::linktype::LinktypeCheck;
+use crate::mail::{parse_imap_response, parse_pop3_response};
+use crate::models::{
+    ArpHeader, BlockDump, CaptureInfo, Classification, CompactProcessingResult, DecodedLayers,
+    Direction, DnsRecord, EthernetHeader, IcmpHeader, Ipv4Header, Ipv6Header, Packet,
+    PacketAnalysis, PacketMetadata, PacketProcessingResult, PacketSummary, TcpHeader, UdpHeader,
+    VlanTag,
+};
+use crate::mpls::parse_mpls_stack;
+use crate::names::{apply_host_names, collect_name_records};
+use crate::nbns::parse_nbns;
+use crate::ndp::{NeighborCache, parse_neighbor_discovery};
+use crate::options::ProcessOptions;
+use crate::ospf::parse_ospf;
+use crate::pcap::{linktype_name, parse_pcap_header};
+use crate::pcapng::{
+    apply_section_options, collect_custom_options, describe_block, interface_counters,
+    interface_labels, packet_comment, parse_epb_flags,
+};
+use crate::ppp::{parse_ppp_header, ppp_protocol_name};
 use crate::preview::{build_ascii_preview, build_hex_preview};
+use crate::ptp::parse_ptp;
+use crate::reassembly::Ipv4Reassembler;
+use crate::sctp::init_addresses;
+use crate::services::service_name;
+use crate::sip::parse_sip;
+use crate::sll::{SllHeader, parse_sll_header, parse_sll2_header};
+use crate::stun::parse_stun;
+use crate::syslog::parse_syslog;
+use crate::tcp::{
+    SMALL_MSS, SYN, TcpFlowTracker, TcpOptions, URG, flag_names, implied_path_mtu,
+    parse_tcp_options,
+};
+use crate::tls::parse_tls_client_hello;
 
 const EM_DASH: &str = "—";
 const ARROW: &str = "\u{2192}";
 
-#[derive(Serialize)]
-struct PacketSummary {
-    info: String,
-    summary: String,
-    time: String,
-    src: String,
-    dst: String,
-    protocol: String,
-    length: usize,
-    hex_preview: String,
-    ascii_preview: String,
-}
-
-#[derive(Serialize)]
-struct Packet {
-    layers: Option<DecodedLayers>,
-    time: String,
-    source: String,
-    destination: String,
-    protocol: String,
-    length: usize,
-    info: String,
-    payload: Vec<u8>,
-}
-
-#[derive(Serialize, Clone)]
-struct EthernetHeader {
-    source_mac: String,
-    destination_mac: String,
-    ethertype: u16,
-}
-
-#[derive(Serialize, Clone)]
-struct Ipv4Header {
-    source: String,
-    destination: String,
-    protocol: u8,
-    header_length: usize,
-    total_length: usize,
-    ttl: u8,
-}
-
-#[derive(Serialize, Clone)]
-struct Ipv6Header {
-    source: String,
-    destination: String,
-    next_header: u8,
-    payload_length: usize,
-    hop_limit: u8,
-}
-
-#[derive(Serialize, Clone)]
-struct TcpHeader {
-    source_port: u16,
-    destination_port: u16,
-}
-
-#[derive(Serialize, Clone)]
-struct UdpHeader {
-    source_port: u16,
-    destination_port: u16,
-    length: u16,
-}
-
-#[derive(Serialize, Clone)]
-struct IcmpHeader {
-    icmp_type: u8,
-    icmp_code: u8,
-    description: String,
-    version: String,
-}
-
-#[derive(Serialize, Clone, Default)]
-struct DecodedLayers {
-    ethernet: Option<EthernetHeader>,
-    ipv4: Option<Ipv4Header>,
-    ipv6: Option<Ipv6Header>,
-    tcp: Option<TcpHeader>,
-    udp: Option<UdpHeader>,
-    icmp: Option<IcmpHeader>,
-}
-
-#[derive(Serialize)]
-struct PacketProcessingResult {
-    packets: Vec<Packet>,
-    warnings: Vec<String>,
-    errors: Vec<String>,
-}
-
-struct PacketMetadata {
-    layers: Option<DecodedLayers>,
-    time: String,
-    source: String,
-    destination: String,
-    protocol: String,
-    summary: String,
-    length: usize,
+/// Replaces the Unicode arrow and em-dash used in summaries and address
+/// placeholders with `->` and `-`, for the `ascii_only` option.
+fn ascii_punctuation(text: String) -> String {
+    if text.is_ascii() {
+        return text;
+    }
+    text.replace(ARROW, "->").replace(EM_DASH, "-")
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone)]
 struct InterfaceInfo {
     linktype: u32,
     ts_offset: u64,
     ts_resolution: u64,
+    /// `if_name`, or `if{id}` when the block does not name the interface.
+    name: String,
+    description: Option<String>,
 }
 
 impl InterfaceInfo {
-    fn from_block(block: &InterfaceDescriptionBlock<'_>) -> InterfaceInfo {
+    fn from_block(block: &InterfaceDescriptionBlock<'_>, id: usize) -> InterfaceInfo {
         let resolution = block.ts_resolution().unwrap_or(1_000_000);
+        let (name, description) = interface_labels(&block.options);
         InterfaceInfo {
             linktype: block.linktype.0 as u32,
             ts_offset: block.ts_offset(),
             ts_resolution: resolution,
+            name: name
+                .filter(|name| !name.is_empty())
+                .unwrap_or_else(|| format!("if{id}")),
+            description,
         }
     }
 }
 
+/// Per-capture state fed every dissected packet in capture order.
 #[derive(Default)]
-struct PacketAnalysis {
-    source: String,
-    layers: DecodedLayers,
-    destination: String,
-    protocol: String,
-    summary: String,
+struct CaptureTrackers {
+    tcp: TcpFlowTracker,
+    arp: ArpConflictTracker,
+    neighbors: NeighborCache,
+    fragments: Ipv4Reassembler,
+}
+
+impl CaptureTrackers {
+    /// Dissects a frame, first feeding any IPv4 fragment it carries to the
+    /// reassembler. The fragment that completes a datagram is dissected with
+    /// the whole datagram in place of its own IP packet.
+    fn analyze(
+        &mut self,
+        linktype: u32,
+        frame: &[u8],
+        options: &ProcessOptions,
+        warnings: &mut Vec<String>,
+    ) -> PacketAnalysis {
+        if let Some((offset, 0x0800)) = network_layer(linktype, frame)
+            && let Some(reassembled) = self.fragments.push(&frame[offset..])
+        {
+            let mut whole = frame[..offset].to_vec();
+            whole.extend_from_slice(&reassembled.datagram);
+            let mut analysis = analyze_payload(linktype, &whole, options, warnings);
+            analysis.summary.push_str(&format!(
+                " [reassembled from {} fragments]",
+                reassembled.fragments
+            ));
+            return analysis;
+        }
+        analyze_payload(linktype, frame, options, warnings)
+    }
+
+    fn observe(&mut self, analysis: &mut PacketAnalysis, warnings: &mut Vec<String>) {
+        self.tcp.annotate(analysis);
+        self.arp.observe(analysis, warnings);
+        self.neighbors.observe(analysis);
+    }
+
+    /// Reports state left over once the capture (or section) has ended.
+    fn finish(&self, warnings: &mut Vec<String>) {
+        let pending = self.fragments.pending();
+        if pending > 0 {
+            push_warning(
+                warnings,
+                format!("{pending} IPv4 datagram(s) missing fragments at end of capture"),
+            );
+        }
+    }
+}
+
+/// Where the network layer begins in a frame of the given linktype, and the
+/// EtherType of what it carries. `None` when the link header is unknown,
+/// truncated, or not followed by a network-layer protocol.
+fn network_layer(linktype: u32, frame: &[u8]) -> Option<(usize, u16)> {
+    let layer = match linktype {
+        1 => {
+            let mut offset = 12;
+            for _ in 0..=MAX_VLAN_TAGS {
+                let ethertype = u16::from_be_bytes([*frame.get(offset)?, *frame.get(offset + 1)?]);
+                match ethertype {
+                    0x8100 | 0x88A8 => offset += 4,
+                    0..0x0600 => return None,
+                    _ => return Some((offset + 2, ethertype)),
+                }
+            }
+            return None;
+        }
+        0 => match u32::from_ne_bytes(frame.get(..4)?.try_into().ok()?) {
+            2 => (4, 0x0800),
+            24 => (4, 0x86DD),
+            _ => return None,
+        },
+        101 => match frame.first()? >> 4 {
+            4 => (0, 0x0800),
+            6 => (0, 0x86DD),
+            _ => return None,
+        },
+        228 => (0, 0x0800),
+        229 => (0, 0x86DD),
+        113 => (16, parse_sll_header(frame)?.protocol),
+        276 => (20, parse_sll2_header(frame)?.protocol),
+        _ => return None,
+    };
+    Some(layer)
+}
+
+/// IP version of the packet in a frame, read from the link header alone so
+/// the `ip_version` filter can skip frames before they are dissected.
+fn frame_ip_version(linktype: u32, frame: &[u8]) -> Option<u8> {
+    let ethertype = match linktype {
+        9 => match parse_ppp_header(frame)?.0 {
+            0x0021 => 0x0800,
+            0x0057 => 0x86DD,
+            _ => return None,
+        },
+        _ => network_layer(linktype, frame)?.1,
+    };
+    match ethertype {
+        0x0800 => Some(4),
+        0x86DD => Some(6),
+        _ => None,
+    }
 }
 
-use crate::pcap::parse_pcap_header;
+fn serialize_result<T: Serialize>(result: &T) -> String {
+    serde_json::to_string(result).unwrap_or_else(|_| {
+        "{\"packets\":[],\"warnings\":[],\"errors\":[],\"capture_info\":{}}".into()
+    })
+}
 
-fn serialize_result(result: &PacketProcessingResult) -> String {
-    serde_json::to_string(result)
-        .unwrap_or_else(|_| "{\"packets\":[],\"warnings\":[],\"errors\":[]}".into())
+/// Appends a warning, folding an immediate repeat of the previous message
+/// into a count suffix such as `(x42)` so corrupt captures stay readable.
+fn push_warning(warnings: &mut Vec<String>, message: String) {
+    if let Some(last) = warnings.last_mut() {
+        if *last == message {
+            *last = format!("{message} (x2)");
+            return;
+        }
+        if let Some(count) = last
+            .strip_prefix(message.as_str())
+            .and_then(|rest| rest.strip_prefix(" (x"))
+            .and_then(|rest| rest.strip_suffix(')'))
+            .and_then(|count| count.parse::<usize>().ok())
+        {
+            *last = format!("{message} (x{})", count + 1);
+            return;
+        }
+    }
+    warnings.push(message);
 }
 
 fn format_timestamp(seconds: i64, fractional: u64, resolution: u64) -> String {
@@ -167,6 +299,19 @@ fn format_timestamp(seconds: i64, fractional: u64, resolution: u64) -> String {
     }
 }
 
+/// Converts a timestamp to nanoseconds since the epoch, saturating at the
+/// `i64` bounds instead of wrapping.
+fn epoch_nanos(seconds: i64, fractional: u64, resolution: u64) -> i64 {
+    let fraction_ns = if resolution == 0 {
+        0
+    } else {
+        (fractional as u128 * 1_000_000_000 / resolution as u128).min(i64::MAX as u128) as i64
+    };
+    seconds
+        .saturating_mul(1_000_000_000)
+        .saturating_add(fraction_ns)
+}
+
 fn decimal_digits(resolution: u64) -> Option<usize> {
     if resolution == 0 {
         return None;
@@ -174,7 +319,7 @@ fn decimal_digits(resolution: u64) -> Option<usize> {
     let mut value = resolution;
     let mut digits = 0usize;
     while value > 1 {
-        if value % 10 != 0 {
+        if !value.is_multiple_of(10) {
             return None;
         }
         value /= 10;
@@ -183,56 +328,163 @@ fn decimal_digits(resolution: u64) -> Option<usize> {
     Some(digits)
 }
 
-fn create_packet(meta: PacketMetadata, payload: &[u8]) -> Packet {
+fn create_packet(meta: PacketMetadata, payload: &[u8], options: &ProcessOptions) -> Packet {
     let PacketMetadata {
         time,
+        epoch_seconds,
         source,
         destination,
         protocol,
         summary,
         length,
+        frame_number,
+        direction,
+        epoch_ns,
+        l3_offset,
+        interface,
+        interface_description,
+        comment,
         layers,
     } = meta;
 
-    let hex_preview = build_hex_preview(payload, 32);
-    let ascii_preview = build_ascii_preview(payload, 32);
-    let summary_payload = PacketSummary {
+    let (mut summary, source, destination) = if options.ascii_only {
+        (
+            ascii_punctuation(summary),
+            ascii_punctuation(source),
+            ascii_punctuation(destination),
+        )
+    } else {
+        (summary, source, destination)
+    };
+    let stored_len = options
+        .max_payload_bytes
+        .map_or(payload.len(), |max| payload.len().min(max));
+    if stored_len < payload.len() {
+        summary.push_str(" [payload truncated]");
+    }
+    let entropy = (options.compute_entropy && payload.len() >= MIN_ENTROPY_SAMPLE)
+        .then(|| shannon_entropy(payload));
+    if entropy.is_some_and(|entropy| entropy > HIGH_ENTROPY) {
+        summary.push_str(" [high entropy]");
+    }
+
+    let (hex_preview, ascii_preview) = if options.preview_len == 0 {
+        (String::new(), String::new())
+    } else {
+        (
+            build_hex_preview(
+                payload,
+                options.preview_len,
+                options.preview_from_end,
+                options.hex_group_size,
+            ),
+            build_ascii_preview(payload, options.preview_len, options.preview_from_end),
+        )
+    };
+    let tcp = layers.as_ref().and_then(|layers| layers.tcp.as_ref());
+    let info = PacketSummary {
         info: summary.clone(),
-        summary: summary.clone(),
-        time: time.clone(),
-        src: source.clone(),
-        dst: destination.clone(),
-        protocol: protocol.clone(),
-        length,
+        summary,
+        protocol_stack: protocol_stack(layers.as_ref(), &protocol),
+        frame_number,
+        direction,
+        epoch_ns,
+        tcp_options: tcp.and_then(TcpOptions::from_header),
+        entropy,
+        interface,
+        interface_description,
         hex_preview,
         ascii_preview,
     };
-    let info = serde_json::to_string(&summary_payload).unwrap_or_else(|_| summary.clone());
 
     Packet {
+        tcp_seq: tcp.and_then(|tcp| tcp.sequence),
+        tcp_ack: tcp.and_then(|tcp| tcp.acknowledgment),
         layers,
         time,
+        epoch_seconds,
         source,
         destination,
         protocol,
         length,
         info,
-        payload: payload.to_vec(),
+        payload: payload[..stored_len].to_vec(),
+        // An offset past the stored bytes would index beyond `payload`.
+        l3_offset: l3_offset.filter(|&offset| offset < stored_len),
+        comment,
+    }
+}
+
+fn analyze_payload(
+    linktype: u32,
+    payload: &[u8],
+    options: &ProcessOptions,
+    warnings: &mut Vec<String>,
+) -> PacketAnalysis {
+    let mut analysis = analyze_link_layer(linktype, payload, options, warnings);
+    if options.verify_ipv4_checksum
+        && let Some(ipv4) = &analysis.layers.ipv4
+        && !ipv4.checksum_valid
+    {
+        analysis.summary.push_str(" [bad checksum]");
+        push_warning(warnings, "IPv4 header checksum mismatch".to_string());
+    }
+    if let Some((offset, 0x0806)) = network_layer(linktype, payload)
+        && arp_claims_ipv6(&payload[offset..])
+    {
+        push_warning(
+            warnings,
+            "ARP frame with IPv6 protocol type 0x86DD (IPv6 uses NDP, not ARP)".to_string(),
+        );
     }
+    // Out-of-band data is rare in legitimate traffic and a known IDS
+    // evasion trick, so it is worth a note even though it is valid TCP.
+    // The pointer is only in the summary, so repeats fold into one warning.
+    if analysis
+        .layers
+        .tcp
+        .as_ref()
+        .is_some_and(|tcp| tcp.urgent_pointer.is_some())
+    {
+        push_warning(
+            warnings,
+            "Note: TCP segment carries urgent data".to_string(),
+        );
+    }
+    analysis
 }
 
-fn analyze_payload(linktype: u32, payload: &[u8]) -> PacketAnalysis {
+fn analyze_link_layer(
+    linktype: u32,
+    payload: &[u8],
+    options: &ProcessOptions,
+    warnings: &mut Vec<String>,
+) -> PacketAnalysis {
+    let decap = Decap::new(options);
     match linktype {
-        1 => analyze_ethernet_frame(payload),
-        0 => analyze_null_loopback(payload)
+        1 => analyze_ethernet_frame(payload, options, decap),
+        0 => analyze_null_loopback(payload, decap)
             .unwrap_or_else(|| fallback_analysis(linktype, payload.len())),
-        101 | 228 => {
-            parse_ipv4_packet(payload).unwrap_or_else(|| fallback_analysis(linktype, payload.len()))
-        }
-        229 => {
-            parse_ipv6_packet(payload).unwrap_or_else(|| fallback_analysis(linktype, payload.len()))
-        }
-        _ => analyze_raw_ip(payload).unwrap_or_else(|| fallback_analysis(linktype, payload.len())),
+        101 | 228 => parse_ipv4_packet(payload, decap)
+            .unwrap_or_else(|| fallback_analysis(linktype, payload.len())),
+        229 => parse_ipv6_packet(payload, decap)
+            .unwrap_or_else(|| fallback_analysis(linktype, payload.len())),
+        9 => analyze_ppp_frame(payload, decap)
+            .unwrap_or_else(|| fallback_analysis(linktype, payload.len())),
+        113 => parse_sll_header(payload)
+            .and_then(|sll| analyze_sll_frame(sll, decap))
+            .unwrap_or_else(|| fallback_analysis(linktype, payload.len())),
+        276 => parse_sll2_header(payload)
+            .and_then(|sll| analyze_sll_frame(sll, decap))
+            .unwrap_or_else(|| fallback_analysis(linktype, payload.len())),
+        _ if options.disable_ip_guess => fallback_analysis(linktype, payload.len()),
+        _ => analyze_raw_ip(payload, decap).unwrap_or_else(|| {
+            let message = format!("Unknown linktype {linktype}, treated as raw IP");
+            if !warnings.contains(&message) {
+                push_warning(warnings, message);
+            }
+            fallback_analysis(linktype, payload.len())
+        }),
     }
 }
 
@@ -246,87 +498,236 @@ fn fallback_analysis(linktype: u32, length: usize) -> PacketAnalysis {
     }
 }
 
-fn analyze_raw_ip(payload: &[u8]) -> Option<PacketAnalysis> {
+fn analyze_raw_ip(payload: &[u8], decap: Decap) -> Option<PacketAnalysis> {
     payload.first().and_then(|byte| match byte >> 4 {
-        4 => parse_ipv4_packet(payload),
-        6 => parse_ipv6_packet(payload),
+        4 => parse_ipv4_packet(payload, decap),
+        6 => parse_ipv6_packet(payload, decap),
         _ => None,
     })
 }
 
-fn analyze_null_loopback(payload: &[u8]) -> Option<PacketAnalysis> {
+fn analyze_null_loopback(payload: &[u8], decap: Decap) -> Option<PacketAnalysis> {
     if payload.len() < 4 {
         return None;
     }
     let family = u32::from_ne_bytes(payload[0..4].try_into().ok()?);
     let data = &payload[4..];
     match family {
-        2 => parse_ipv4_packet(data),
-        24 => parse_ipv6_packet(data),
+        2 => parse_ipv4_packet(data, decap),
+        24 => parse_ipv6_packet(data, decap),
         _ => None,
     }
 }
 
-fn analyze_ethernet_frame(frame: &[u8]) -> PacketAnalysis {
+fn analyze_sll_frame(sll: SllHeader<'_>, decap: Decap) -> Option<PacketAnalysis> {
+    let address = sll.address.unwrap_or_else(|| EM_DASH.to_string());
+    dispatch_ethertype(sll.protocol, sll.payload, &address, EM_DASH, decap)
+}
+
+/// Stacked VLAN tags unwrapped before a frame is treated as malformed.
+const MAX_VLAN_TAGS: usize = 4;
+
+/// Appended to the outer packet's summary when a tunnel is left unopened.
+const DECAP_LIMIT_NOTE: &str = " [max decapsulation depth reached]";
+
+/// How many more tunnel layers a dissector may unwrap, so crafted captures
+/// cannot nest encapsulations without bound. It also carries the caller's
+/// options down to frames dissected inside a tunnel.
+#[derive(Clone, Copy)]
+struct Decap<'a> {
+    remaining: usize,
+    options: &'a ProcessOptions,
+}
+
+static DEFAULT_OPTIONS: LazyLock<ProcessOptions> = LazyLock::new(ProcessOptions::default);
+
+impl<'a> Decap<'a> {
+    fn new(options: &'a ProcessOptions) -> Self {
+        Decap {
+            remaining: options.max_decap_depth,
+            options,
+        }
+    }
+
+    /// Budget for the next encapsulated layer, or `None` once spent.
+    fn next(self) -> Option<Self> {
+        let remaining = self.remaining.checked_sub(1)?;
+        Some(Decap { remaining, ..self })
+    }
+}
+
+impl Default for Decap<'static> {
+    fn default() -> Self {
+        Decap::new(&DEFAULT_OPTIONS)
+    }
+}
+
+fn analyze_ethernet_frame(frame: &[u8], options: &ProcessOptions, decap: Decap) -> PacketAnalysis {
+    if frame.is_empty() {
+        return fallback_analysis(1, 0);
+    }
     if frame.len() < 14 {
-        return fallback_analysis(1, frame.len());
+        // Runts are a capture or link fault, so they are labelled as errors
+        // rather than as an unknown protocol.
+        return PacketAnalysis {
+            source: EM_DASH.to_string(),
+            destination: EM_DASH.to_string(),
+            protocol: "Error".to_string(),
+            summary: format!("Runt frame ({} bytes, too short for Ethernet)", frame.len()),
+            layers: DecodedLayers::default(),
+        };
     }
     let dst_mac = format_mac(&frame[0..6]);
     let src_mac = format_mac(&frame[6..12]);
     let ethertype = u16::from_be_bytes(frame[12..14].try_into().ok().unwrap_or([0, 0]));
+    // 802.1ad S-tags and 802.1Q C-tags are unwrapped so the inner ethertype
+    // (IP or ARP) goes through the same dispatch as an untagged frame.
+    let mut vlan = Vec::new();
+    let mut inner_type = ethertype;
+    let mut payload = &frame[14..];
+    while matches!(inner_type, 0x8100 | 0x88A8)
+        && vlan.len() < MAX_VLAN_TAGS
+        && let Some(tag) = payload.get(..4)
+    {
+        let tci = u16::from_be_bytes([tag[0], tag[1]]);
+        vlan.push(VlanTag {
+            id: tci & 0x0FFF,
+            pcp: (tci >> 13) as u8,
+        });
+        inner_type = u16::from_be_bytes([tag[2], tag[3]]);
+        payload = &payload[4..];
+    }
     let ethernet = EthernetHeader {
         source_mac: src_mac.clone(),
         destination_mac: dst_mac.clone(),
         ethertype,
+        vlan,
     };
-    match ethertype {
-        0x0800 => {
-            if let Some(mut analysis) = parse_ipv4_packet(&frame[14..]) {
-                if analysis.source == EM_DASH {
-                    analysis.source = src_mac.clone();
-                }
-                if analysis.destination == EM_DASH {
-                    analysis.destination = dst_mac.clone();
-                }
-                analysis.layers.ethernet = Some(ethernet.clone());
-                return analysis;
-            }
-        }
-        0x86DD => {
-            if let Some(mut analysis) = parse_ipv6_packet(&frame[14..]) {
-                if analysis.source == EM_DASH {
-                    analysis.source = src_mac.clone();
-                }
-                if analysis.destination == EM_DASH {
-                    analysis.destination = dst_mac.clone();
-                }
-                analysis.layers.ethernet = Some(ethernet.clone());
-                return analysis;
-            }
+
+    let mut analysis = dispatch_ethertype(inner_type, payload, &src_mac, &dst_mac, decap)
+        .unwrap_or_else(|| PacketAnalysis {
+            source: src_mac.clone(),
+            destination: dst_mac.clone(),
+            protocol: format!("EtherType 0x{inner_type:04X}"),
+            summary: format!(
+                "Ethernet 0x{inner_type:04X} {ARROW} captured {} bytes",
+                frame.len()
+            ),
+            layers: DecodedLayers::default(),
+        });
+    if !ethernet.vlan.is_empty() {
+        let ids: Vec<String> = ethernet.vlan.iter().map(|tag| tag.id.to_string()).collect();
+        analysis.summary = format!("VLAN {} {}", ids.join(">"), analysis.summary);
+    }
+    analysis.layers.ethernet = Some(ethernet);
+    if frame.len() > options.jumbo_frame_threshold {
+        analysis.summary.push_str(" [jumbo]");
+    }
+    analysis
+}
+
+fn dispatch_ethertype(
+    ethertype: u16,
+    payload: &[u8],
+    src_mac: &str,
+    dst_mac: &str,
+    decap: Decap,
+) -> Option<PacketAnalysis> {
+    let mut analysis = match ethertype {
+        0x0800 => parse_ipv4_packet(payload, decap)?,
+        0x86DD => parse_ipv6_packet(payload, decap)?,
+        0x0806 => return parse_arp_packet(payload, src_mac, dst_mac),
+        0x8847 | 0x8848 => analyze_mpls(payload, decap)?,
+        0x8808 => analyze_mac_control(payload)?,
+        0x88F7 => PacketAnalysis {
+            source: EM_DASH.to_string(),
+            destination: EM_DASH.to_string(),
+            protocol: "PTP".to_string(),
+            summary: parse_ptp(payload)?,
+            layers: DecodedLayers::default(),
+        },
+        _ => return None,
+    };
+    if analysis.source == EM_DASH {
+        analysis.source = src_mac.to_string();
+    }
+    if analysis.destination == EM_DASH {
+        analysis.destination = dst_mac.to_string();
+    }
+    Some(analysis)
+}
+
+/// Describes an 802.3x MAC Control frame. PAUSE frames mean the sender's
+/// receive buffers are filling, so they are labelled as control traffic.
+fn analyze_mac_control(payload: &[u8]) -> Option<PacketAnalysis> {
+    let opcode = u16::from_be_bytes([*payload.first()?, *payload.get(1)?]);
+    let summary = match (opcode, payload.get(2..4)) {
+        (0x0001, Some(quanta)) => format!(
+            "Ethernet PAUSE time={}",
+            u16::from_be_bytes([quanta[0], quanta[1]])
+        ),
+        (0x0101, _) => "Ethernet priority PAUSE".to_string(),
+        _ => format!("Ethernet MAC Control opcode 0x{opcode:04X}"),
+    };
+    Some(PacketAnalysis {
+        source: EM_DASH.to_string(),
+        destination: EM_DASH.to_string(),
+        protocol: "Control".to_string(),
+        summary,
+        layers: DecodedLayers::default(),
+    })
+}
+
+/// Decodes an MPLS label stack and guesses the payload's IP version from its
+/// first nibble, since MPLS carries no next-protocol field.
+fn analyze_mpls(payload: &[u8], decap: Decap) -> Option<PacketAnalysis> {
+    let (labels, inner) = parse_mpls_stack(payload);
+    let top = labels.first()?.label;
+    let nested = decap.next();
+    let dissected = inner
+        .zip(nested)
+        .and_then(|(inner, decap)| analyze_raw_ip(inner, decap));
+    let mut analysis = match dissected {
+        Some(mut analysis) => {
+            analysis.summary = format!("MPLS {top} {}", analysis.summary);
+            analysis
         }
-        0x0806 => {
-            if let Some(analysis) = parse_arp_packet(&frame[14..], &src_mac, &dst_mac) {
-                return analysis;
+        None => {
+            let detail = if inner.is_some() {
+                format!("{}-label stack", labels.len())
+            } else {
+                "no bottom of stack".to_string()
+            };
+            PacketAnalysis {
+                source: EM_DASH.to_string(),
+                destination: EM_DASH.to_string(),
+                protocol: "MPLS".to_string(),
+                summary: format!("MPLS {top} ({detail})"),
+                layers: DecodedLayers::default(),
             }
         }
-        _ => {}
+    };
+    if inner.is_some() && nested.is_none() {
+        analysis.summary.push_str(DECAP_LIMIT_NOTE);
     }
-    PacketAnalysis {
-        source: src_mac,
-        destination: dst_mac,
-        protocol: format!("EtherType 0x{ethertype:04X}"),
-        summary: format!(
-            "Ethernet 0x{ethertype:04X} {ARROW} captured {} bytes",
-            frame.len()
-        ),
-        layers: DecodedLayers {
-            ethernet: Some(ethernet),
-            ..DecodedLayers::default()
-        },
+    analysis.layers.mpls = labels;
+    Some(analysis)
+}
+
+/// One's-complement sum of 16-bit words (RFC 1071). Summing a header that
+/// includes its own checksum field yields zero when the checksum is correct.
+fn internet_checksum(data: &[u8]) -> u16 {
+    let mut sum: u32 = data
+        .chunks(2)
+        .map(|pair| u32::from(u16::from_be_bytes([pair[0], *pair.get(1).unwrap_or(&0)])))
+        .sum();
+    while sum > 0xFFFF {
+        sum = (sum & 0xFFFF) + (sum >> 16);
     }
+    !(sum as u16)
 }
 
-fn parse_ipv4_packet(packet: &[u8]) -> Option<PacketAnalysis> {
+fn parse_ipv4_packet(packet: &[u8], decap: Decap) -> Option<PacketAnalysis> {
     if packet.len() < 20 {
         return None;
     }
@@ -366,63 +767,102 @@ fn parse_ipv4_packet(packet: &[u8]) -> Option<PacketAnalysis> {
                 header_length: ihl,
                 total_length,
                 ttl: packet[8],
+                checksum_valid: internet_checksum(&packet[..ihl]) == 0,
             }),
             ..DecodedLayers::default()
         },
     };
 
+    // Only the first fragment starts with the transport header.
+    let fragment_offset = u16::from_be_bytes([packet[6], packet[7]]) & 0x1FFF;
     match protocol {
-        6 | 17 | 132 => {
-            if payload.len() >= 4 {
-                let src_port = u16::from_be_bytes(payload[0..2].try_into().ok()?);
-                let dst_port = u16::from_be_bytes(payload[2..4].try_into().ok()?);
-                analysis.source = format_port(&src_ip, src_port);
-                analysis.destination = format_port(&dst_ip, dst_port);
-                if protocol == 6 {
-                    analysis.layers.tcp = Some(TcpHeader {
-                        source_port: src_port,
-                        destination_port: dst_port,
-                    });
-                } else if protocol == 17 {
-                    let udp_len = if payload.len() >= 6 {
-                        u16::from_be_bytes(payload[4..6].try_into().ok().unwrap_or([0, 0]))
-                    } else {
-                        0
-                    };
-                    analysis.layers.udp = Some(UdpHeader {
-                        source_port: src_port,
-                        destination_port: dst_port,
-                        length: udp_len,
-                    });
+        _ if fragment_offset != 0 => {
+            analysis.summary.push_str(" [fragment]");
+        }
+        6 | 17 | 132 if payload.len() >= 4 => {
+            decode_transport(protocol, payload, &src_ip, &dst_ip, &mut analysis, decap);
+        }
+        1 if payload.len() >= 2 => {
+            let icmp_type = payload[0];
+            let icmp_code = payload[1];
+            let description = describe_icmpv4(icmp_type, icmp_code, payload);
+            analysis.layers.icmp = Some(IcmpHeader {
+                icmp_type,
+                icmp_code,
+                description: description.clone(),
+                version: "ICMP".to_string(),
+            });
+            analysis.summary = format!("ICMP {src_ip} {ARROW} {dst_ip} ({description})");
+        }
+        4 | 41 | 47 => match decap.next() {
+            Some(decap) => {
+                let inner = if protocol == 47 {
+                    decapsulate_gre(payload, &src_ip, &dst_ip, &mut analysis, decap)
+                } else {
+                    decapsulate_ip(payload, decap)
+                };
+                if let Some(inner) = inner {
+                    return Some(inner);
                 }
-                analysis.summary = format!(
-                    "{protocol_name} {} {ARROW} {}",
-                    analysis.source, analysis.destination
-                );
+            }
+            None => analysis.summary.push_str(DECAP_LIMIT_NOTE),
+        },
+        2 => {
+            if let Some(description) = parse_igmp(payload) {
+                analysis.summary = description;
             }
         }
-        1 => {
-            if payload.len() >= 2 {
-                let icmp_type = payload[0];
-                let icmp_code = payload[1];
-                let description = describe_icmpv4(icmp_type, icmp_code);
-                analysis.layers.icmp = Some(IcmpHeader {
-                    icmp_type,
-                    icmp_code,
-                    description: description.clone(),
-                    version: "ICMP".to_string(),
-                });
-                analysis.summary = format!("ICMP {src_ip} {ARROW} {dst_ip} ({description})");
+        50 => {
+            if let Some(description) = parse_esp(payload) {
+                analysis.summary = description;
+            }
+        }
+        89 => {
+            if let Some(description) = parse_ospf(payload) {
+                analysis.summary = description;
             }
         }
         _ => {}
     }
 
     analysis.summary = build_summary_from_layers(&analysis.layers, analysis.summary);
+    if has_router_alert(&packet[20..ihl]) {
+        match protocol {
+            2 | 46 => analysis
+                .summary
+                .push_str(&format!(" [Router Alert: {protocol_name}]")),
+            _ => analysis.summary.push_str(" [Router Alert]"),
+        }
+    }
     Some(analysis)
 }
 
-fn parse_ipv6_packet(packet: &[u8]) -> Option<PacketAnalysis> {
+/// Walks the IPv4 options area looking for Router Alert (RFC 2113).
+fn has_router_alert(options: &[u8]) -> bool {
+    let mut offset = 0;
+    while offset < options.len() {
+        match options[offset] {
+            0 => return false,
+            1 => offset += 1,
+            option_type => {
+                let Some(&length) = options.get(offset + 1) else {
+                    return false;
+                };
+                let length = length as usize;
+                if length < 2 || offset + length > options.len() {
+                    return false;
+                }
+                if option_type == 148 {
+                    return true;
+                }
+                offset += length;
+            }
+        }
+    }
+    false
+}
+
+fn parse_ipv6_packet(packet: &[u8], decap: Decap) -> Option<PacketAnalysis> {
     if packet.len() < 40 {
         return None;
     }
@@ -435,9 +875,17 @@ fn parse_ipv6_packet(packet: &[u8]) -> Option<PacketAnalysis> {
     let src_ip = Ipv6Addr::from(src_bytes).to_string();
     let dst_ip = Ipv6Addr::from(dst_bytes).to_string();
     let mut offset = 40usize;
+    let mut tunnel_limit = None;
+    let mut alert = None;
+
+    let mut chain = Vec::new();
+    let mut malformed_options = false;
 
     // Naively skip a few common extension headers.
     for _ in 0..4 {
+        if matches!(next_header, 0 | 43 | 44 | 51 | 60) {
+            chain.push(next_header);
+        }
         match next_header {
             0 | 43 | 60 => {
                 if packet.len() < offset + 8 {
@@ -447,6 +895,15 @@ fn parse_ipv6_packet(packet: &[u8]) -> Option<PacketAnalysis> {
                 if packet.len() < offset + hdr_len {
                     break;
                 }
+                let options = &packet[offset + 2..offset + hdr_len];
+                if next_header != 43 && !options_fill_header(options) {
+                    malformed_options = true;
+                }
+                match next_header {
+                    0 => alert = alert.or(router_alert(options)),
+                    60 => tunnel_limit = tunnel_limit.or(tunnel_encapsulation_limit(options)),
+                    _ => {}
+                }
                 next_header = packet[offset];
                 offset += hdr_len;
             }
@@ -495,119 +952,469 @@ fn parse_ipv6_packet(packet: &[u8]) -> Option<PacketAnalysis> {
     };
 
     match next_header {
-        6 | 17 | 132 => {
-            if payload.len() >= 4 {
-                let src_port = u16::from_be_bytes(payload[0..2].try_into().ok()?);
-                let dst_port = u16::from_be_bytes(payload[2..4].try_into().ok()?);
-                analysis.source = format_port(&src_ip, src_port);
-                analysis.destination = format_port(&dst_ip, dst_port);
-                if next_header == 6 {
-                    analysis.layers.tcp = Some(TcpHeader {
-                        source_port: src_port,
-                        destination_port: dst_port,
-                    });
-                } else if next_header == 17 {
-                    let udp_len = if payload.len() >= 6 {
-                        u16::from_be_bytes(payload[4..6].try_into().ok().unwrap_or([0, 0]))
-                    } else {
-                        0
-                    };
-                    analysis.layers.udp = Some(UdpHeader {
-                        source_port: src_port,
-                        destination_port: dst_port,
-                        length: udp_len,
-                    });
+        6 | 17 | 132 if payload.len() >= 4 => {
+            decode_transport(next_header, payload, &src_ip, &dst_ip, &mut analysis, decap);
+        }
+        58 if payload.len() >= 2 => {
+            let icmp_type = payload[0];
+            let icmp_code = payload[1];
+            let description = describe_icmpv6(icmp_type, icmp_code, payload);
+            analysis.layers.icmp = Some(IcmpHeader {
+                icmp_type,
+                icmp_code,
+                description: description.clone(),
+                version: "ICMPv6".to_string(),
+            });
+            analysis.summary = format!("ICMPv6 {src_ip} {ARROW} {dst_ip} ({description})");
+            analysis.layers.ndp = parse_neighbor_discovery(payload);
+        }
+        4 | 41 | 47 => match decap.next() {
+            Some(decap) => {
+                let inner = if next_header == 47 {
+                    decapsulate_gre(payload, &src_ip, &dst_ip, &mut analysis, decap)
+                } else {
+                    decapsulate_ip(payload, decap)
+                };
+                if let Some(inner) = inner {
+                    return Some(inner);
                 }
-                analysis.summary = format!(
-                    "{protocol_name} {} {ARROW} {}",
-                    analysis.source, analysis.destination
-                );
+            }
+            None => analysis.summary.push_str(DECAP_LIMIT_NOTE),
+        },
+        50 => {
+            if let Some(description) = parse_esp(payload) {
+                analysis.summary = description;
             }
         }
-        58 => {
-            if payload.len() >= 2 {
-                let icmp_type = payload[0];
-                let icmp_code = payload[1];
-                let description = describe_icmpv6(icmp_type, icmp_code);
-                analysis.layers.icmp = Some(IcmpHeader {
-                    icmp_type,
-                    icmp_code,
-                    description: description.clone(),
-                    version: "ICMPv6".to_string(),
-                });
-                analysis.summary = format!("ICMPv6 {src_ip} {ARROW} {dst_ip} ({description})");
+        89 => {
+            if let Some(description) = parse_ospf(payload) {
+                analysis.summary = description;
             }
         }
         _ => {}
     }
 
     analysis.summary = build_summary_from_layers(&analysis.layers, analysis.summary);
+    if alert.is_some() {
+        let mld = analysis
+            .layers
+            .icmp
+            .as_ref()
+            .and_then(|icmp| mld_version(icmp.icmp_type, payload.len()));
+        match (alert, mld) {
+            (Some(0), Some(version)) => {
+                let description = describe_icmpv6(payload[0], payload[1], payload);
+                analysis.protocol = version.to_string();
+                analysis.summary = format!(
+                    "{version} {src_ip} {ARROW} {dst_ip} ({description}) [Router Alert: MLD]"
+                );
+            }
+            _ => analysis.summary.push_str(" [Router Alert]"),
+        }
+    }
+    if !extension_order_valid(&chain) {
+        analysis.summary.push_str(" [ext header order violation]");
+    }
+    if malformed_options {
+        analysis.summary.push_str(" [malformed ext header options]");
+    }
+    if let Some(limit) = tunnel_limit {
+        analysis
+            .summary
+            .push_str(&format!(" [tunnel encap limit {limit}]"));
+    }
     Some(analysis)
 }
 
-fn parse_arp_packet(packet: &[u8], src_mac: &str, dst_mac: &str) -> Option<PacketAnalysis> {
-    if packet.len() < 28 {
-        return None;
+/// Dissects the packet carried in a GRE tunnel, prefixing its summary with
+/// the tunnel, e.g. `GRE > TCP 10.0.0.1:443 → 10.0.0.2:51000`. PPTP
+/// (enhanced GRE) frames without an inner IP packet are described in place
+/// on `analysis` between `src_ip` and `dst_ip`, and `None` is returned.
+fn decapsulate_gre(
+    payload: &[u8],
+    src_ip: &str,
+    dst_ip: &str,
+    analysis: &mut PacketAnalysis,
+    decap: Decap,
+) -> Option<PacketAnalysis> {
+    let gre = parse_gre(payload)?;
+    let tunnel = match (gre.call_id, gre.key) {
+        (Some(call_id), _) => format!("PPTP GRE call=0x{call_id:04X}"),
+        (None, Some(key)) => format!("GRE key {key}"),
+        (None, None) => "GRE".to_string(),
+    };
+    // PPP control frames have no inner packet, only a description.
+    let mut control = None;
+    let inner = match gre.protocol_type {
+        0x0800 => parse_ipv4_packet(gre.payload, decap),
+        0x86DD => parse_ipv6_packet(gre.payload, decap),
+        0x880B => parse_ppp_header(gre.payload).and_then(|(protocol, payload)| {
+            let inner = ppp_ip_packet(protocol, payload, decap);
+            if inner.is_none() {
+                control = Some(describe_ppp_frame(protocol));
+            }
+            inner
+        }),
+        _ => None,
+    };
+    match inner {
+        Some(mut inner) => {
+            inner.summary = format!("{tunnel} > {}", inner.summary);
+            Some(inner)
+        }
+        None if gre.call_id.is_some() => {
+            let detail = control
+                .map(|control| format!(" ({})", control.summary))
+                .unwrap_or_default();
+            analysis.protocol = "PPTP".to_string();
+            analysis.summary = format!("{tunnel} {src_ip} {ARROW} {dst_ip}{detail}");
+            None
+        }
+        _ => None,
     }
-    let hw_type = u16::from_be_bytes(packet[0..2].try_into().unwrap());
-    let proto_type = u16::from_be_bytes(packet[2..4].try_into().unwrap());
-    let hw_len = packet[4] as usize;
-    let proto_len = packet[5] as usize;
-    let operation = u16::from_be_bytes(packet[6..8].try_into().unwrap());
+}
 
-    if hw_type != 1 || proto_type != 0x0800 || hw_len != 6 || proto_len != 4 {
-        return None;
-    }
-    if packet.len() < 8 + 2 * (hw_len + proto_len) {
-        return None;
-    }
-    let sender_mac = format_mac(&packet[8..14]);
-    let sender_ip = Ipv4Addr::new(packet[14], packet[15], packet[16], packet[17]).to_string();
-    let target_mac = format_mac(&packet[18..24]);
-    let target_ip = Ipv4Addr::new(packet[24], packet[25], packet[26], packet[27]).to_string();
+/// Dissects an IPv4 or IPv6 packet tunnelled directly in IP (protocols 4 and
+/// 41), prefixing its summary like a GRE tunnel's: `IP-in-IP > ...`.
+fn decapsulate_ip(payload: &[u8], decap: Decap) -> Option<PacketAnalysis> {
+    let mut inner = analyze_raw_ip(payload, decap)?;
+    inner.summary = format!("IP-in-IP > {}", inner.summary);
+    Some(inner)
+}
 
-    let (source, destination, summary) = match operation {
-        1 => (
-            sender_ip.clone(),
-            target_ip.clone(),
-            format!("ARP who-has {target_ip} tell {sender_ip}"),
-        ),
-        2 => (
-            sender_ip.clone(),
-            target_ip.clone(),
-            format!("ARP reply {sender_ip} is-at {sender_mac}"),
-        ),
-        _ => (
-            sender_ip.clone(),
-            target_ip.clone(),
-            format!("ARP op {operation} {sender_ip} {ARROW} {target_ip}"),
-        ),
-    };
+/// Dissects a PPP frame, recursing into IPv4/IPv6 and naming control
+/// protocols. Returns `None` for frames without a valid protocol field.
+fn analyze_ppp_frame(frame: &[u8], decap: Decap) -> Option<PacketAnalysis> {
+    let (protocol, payload) = parse_ppp_header(frame)?;
+    Some(ppp_ip_packet(protocol, payload, decap).unwrap_or_else(|| describe_ppp_frame(protocol)))
+}
 
-    Some(PacketAnalysis {
-        source,
-        destination,
-        protocol: "ARP".to_string(),
-        summary: format!(
-            "{summary} ({} → {})",
-            src_mac,
-            if operation == 2 {
-                target_mac
-            } else {
-                dst_mac.to_string()
-            }
-        ),
+/// Dissects the IPv4 or IPv6 packet carried by a PPP frame, or `None` when
+/// the frame carries anything else.
+fn ppp_ip_packet(protocol: u16, payload: &[u8], decap: Decap) -> Option<PacketAnalysis> {
+    match protocol {
+        0x0021 => parse_ipv4_packet(payload, decap),
+        0x0057 => parse_ipv6_packet(payload, decap),
+        _ => None,
+    }
+}
+
+/// Names the protocol of a PPP frame that carries no IP packet.
+fn describe_ppp_frame(protocol: u16) -> PacketAnalysis {
+    let name = ppp_protocol_name(protocol)
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("0x{protocol:04X}"));
+    PacketAnalysis {
+        source: EM_DASH.to_string(),
+        destination: EM_DASH.to_string(),
+        protocol: "PPP".to_string(),
+        summary: format!("PPP {name}"),
         layers: DecodedLayers::default(),
-    })
+    }
 }
 
-fn map_ip_protocol(value: u8) -> &'static str {
-    match value {
+fn decode_transport(
+    protocol: u8,
+    segment: &[u8],
+    src_ip: &str,
+    dst_ip: &str,
+    analysis: &mut PacketAnalysis,
+    decap: Decap,
+) {
+    let src_port = u16::from_be_bytes([segment[0], segment[1]]);
+    let dst_port = u16::from_be_bytes([segment[2], segment[3]]);
+    analysis.source = format_port(src_ip, src_port);
+    analysis.destination = format_port(dst_ip, dst_port);
+    analysis.summary = format!(
+        "{} {} {ARROW} {}",
+        analysis.protocol, analysis.source, analysis.destination
+    );
+    // The lower port is the likelier server side of the conversation.
+    if let Some(service) = service_name(src_port.min(dst_port)) {
+        analysis.summary.push_str(&format!(" ({service})"));
+    }
+    if protocol == 6 {
+        let header_len = transport_header_len(protocol, segment);
+        let window = segment
+            .get(14..16)
+            .map_or(0, |bytes| u16::from_be_bytes([bytes[0], bytes[1]]));
+        let options = segment
+            .get(20..header_len)
+            .map(parse_tcp_options)
+            .unwrap_or_default();
+        let flags = segment.get(13).copied().unwrap_or(0);
+        if let Some(names) = flag_names(flags) {
+            analysis.summary.push_str(&format!(" [{names}]"));
+        }
+        let mss = options.mss.filter(|_| flags & SYN != 0);
+        let ipv6 = analysis.layers.ipv6.is_some();
+        let read_u32 = |offset: usize| {
+            let bytes = segment.get(offset..offset + 4)?;
+            Some(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+        };
+        analysis.layers.tcp = Some(TcpHeader {
+            source_port: src_port,
+            destination_port: dst_port,
+            sequence: read_u32(4),
+            acknowledgment: read_u32(8),
+            flags,
+            window,
+            window_scale: options.window_scale,
+            effective_window: u32::from(window),
+            mss,
+            path_mtu: mss.map(|mss| implied_path_mtu(mss, ipv6)),
+            sack_permitted: options.sack_permitted,
+            timestamps: options.timestamps,
+            urgent_pointer: segment
+                .get(18..20)
+                .filter(|_| flags & URG != 0)
+                .map(|bytes| u16::from_be_bytes([bytes[0], bytes[1]])),
+        });
+    } else if protocol == 17 {
+        let udp_len = if segment.len() >= 6 {
+            u16::from_be_bytes([segment[4], segment[5]])
+        } else {
+            0
+        };
+        analysis.layers.udp = Some(UdpHeader {
+            source_port: src_port,
+            destination_port: dst_port,
+            length: udp_len,
+        });
+    }
+
+    let data = transport_payload(protocol, segment);
+    if matches!(protocol, 6 | 17) && (src_port == 53 || dst_port == 53) {
+        analysis.layers.dns = parse_dns(data, protocol == 6);
+        if let Some(dns) = &analysis.layers.dns {
+            analysis.protocol = "DNS".to_string();
+            analysis.summary = describe_dns(dns);
+        }
+    }
+    if let Some((name, description)) =
+        describe_application(protocol, src_port, dst_port, data, decap)
+    {
+        analysis.protocol = name.to_string();
+        analysis.summary = description;
+    }
+    if let Some(tcp) = &analysis.layers.tcp
+        && let Some(mss) = tcp.mss
+        && mss < SMALL_MSS
+    {
+        analysis.summary.push_str(&format!(" [small MSS {mss}]"));
+    }
+    if let Some((tsval, tsecr)) = analysis.layers.tcp.as_ref().and_then(|tcp| tcp.timestamps) {
+        analysis
+            .summary
+            .push_str(&format!(" [TSval={tsval} TSecr={tsecr}]"));
+    }
+    if let Some(pointer) = analysis
+        .layers
+        .tcp
+        .as_ref()
+        .and_then(|tcp| tcp.urgent_pointer)
+    {
+        analysis
+            .summary
+            .push_str(&format!(" [urgent ptr={pointer}]"));
+    }
+    if protocol == 132 {
+        let addresses = init_addresses(segment);
+        if !addresses.is_empty() {
+            analysis
+                .summary
+                .push_str(&format!(" [also {}]", addresses.join(", ")));
+        }
+    }
+}
+
+/// Returns the application data carried by a TCP or UDP segment.
+fn transport_payload(protocol: u8, segment: &[u8]) -> &[u8] {
+    match transport_header_len(protocol, segment) {
+        0 => &[],
+        header_len => segment.get(header_len..).unwrap_or(&[]),
+    }
+}
+
+/// Length of the TCP or UDP header at the start of `segment`, or zero when
+/// it cannot be determined.
+fn transport_header_len(protocol: u8, segment: &[u8]) -> usize {
+    match protocol {
+        6 if segment.len() >= 20 => (segment[12] >> 4) as usize * 4,
+        17 => 8,
+        _ => 0,
+    }
+}
+
+/// Identifies well-known application protocols by port first, then falls
+/// back to payload heuristics for protocols that use dynamic ports.
+fn describe_application(
+    protocol: u8,
+    src_port: u16,
+    dst_port: u16,
+    data: &[u8],
+    decap: Decap,
+) -> Option<(&'static str, String)> {
+    let on_port = |port: u16| src_port == port || dst_port == port;
+    if on_port(88)
+        && let Some(description) = parse_kerberos(data, protocol == 6)
+    {
+        return Some(("Kerberos", description));
+    }
+    if protocol == 17
+        && (on_port(67) || on_port(68))
+        && let Some(description) = parse_dhcp(data)
+    {
+        return Some(("DHCP", description));
+    }
+    if protocol == 17
+        && on_port(137)
+        && let Some(description) = parse_nbns(data)
+    {
+        return Some(("NBNS", description));
+    }
+    if protocol == 6
+        && on_port(21)
+        && let Some(description) = parse_ftp(data)
+    {
+        return Some(("FTP", description));
+    }
+    if protocol == 17
+        && on_port(514)
+        && let Some(description) = parse_syslog(data)
+    {
+        return Some(("Syslog", description));
+    }
+    if protocol == 17
+        && (on_port(319) || on_port(320))
+        && let Some(description) = parse_ptp(data)
+    {
+        return Some(("PTP", description));
+    }
+    if protocol == 6
+        && (on_port(110) || on_port(995))
+        && let Some(response) = parse_pop3_response(data)
+    {
+        return Some(response);
+    }
+    if protocol == 6
+        && (on_port(143) || on_port(993))
+        && let Some(response) = parse_imap_response(data)
+    {
+        return Some(response);
+    }
+    if protocol == 17
+        && on_port(5247)
+        && let Some(description) = parse_capwap(data, decap)
+    {
+        return Some(("CAPWAP", description));
+    }
+    if on_port(5060)
+        && let Some(description) = parse_sip(data)
+    {
+        return Some(("SIP", description));
+    }
+    if protocol == 6
+        && on_port(443)
+        && let Some(description) = parse_tls_client_hello(data)
+    {
+        return Some(("TLS", description));
+    }
+    if protocol == 6
+        && let Some(description) = parse_http2(data)
+    {
+        return Some(("HTTP2", description));
+    }
+    if protocol == 17
+        && let Some(description) = parse_dtls(data)
+    {
+        return Some(("DTLS", description));
+    }
+    // STUN runs on negotiated ports, so rely on its magic cookie instead.
+    if protocol == 17
+        && let Some(description) = parse_stun(data)
+    {
+        return Some(("STUN", description));
+    }
+    None
+}
+
+/// Whether an ARP packet declares the IPv6 ethertype as its protocol type.
+/// IPv6 resolves neighbors with NDP, so such frames come from broken
+/// generators; `parse_arp_packet` rejects them like any other protocol.
+fn arp_claims_ipv6(packet: &[u8]) -> bool {
+    packet.get(2..4) == Some(&[0x86, 0xDD])
+}
+
+fn parse_arp_packet(packet: &[u8], src_mac: &str, dst_mac: &str) -> Option<PacketAnalysis> {
+    if packet.len() < 28 {
+        return None;
+    }
+    let hw_type = u16::from_be_bytes(packet[0..2].try_into().unwrap());
+    let proto_type = u16::from_be_bytes(packet[2..4].try_into().unwrap());
+    let hw_len = packet[4] as usize;
+    let proto_len = packet[5] as usize;
+    let operation = u16::from_be_bytes(packet[6..8].try_into().unwrap());
+
+    if hw_type != 1 || proto_type != 0x0800 || hw_len != 6 || proto_len != 4 {
+        return None;
+    }
+    if packet.len() < 8 + 2 * (hw_len + proto_len) {
+        return None;
+    }
+    let sender_mac = format_mac(&packet[8..14]);
+    let sender_ip = Ipv4Addr::new(packet[14], packet[15], packet[16], packet[17]).to_string();
+    let target_mac = format_mac(&packet[18..24]);
+    let target_ip = Ipv4Addr::new(packet[24], packet[25], packet[26], packet[27]).to_string();
+
+    let (source, destination, summary) = match operation {
+        1 => (
+            sender_ip.clone(),
+            target_ip.clone(),
+            format!("ARP who-has {target_ip} tell {sender_ip}"),
+        ),
+        2 => (
+            sender_ip.clone(),
+            target_ip.clone(),
+            format!("ARP reply {sender_ip} is-at {sender_mac}"),
+        ),
+        _ => (
+            sender_ip.clone(),
+            target_ip.clone(),
+            format!("ARP op {operation} {sender_ip} {ARROW} {target_ip}"),
+        ),
+    };
+
+    Some(PacketAnalysis {
+        source,
+        destination,
+        protocol: "ARP".to_string(),
+        summary: format!(
+            "{summary} ({} {ARROW} {})",
+            src_mac,
+            if operation == 2 { &target_mac } else { dst_mac }
+        ),
+        layers: DecodedLayers {
+            arp: Some(ArpHeader {
+                operation,
+                sender_mac,
+                sender_ip,
+                target_mac,
+                target_ip,
+            }),
+            ..DecodedLayers::default()
+        },
+    })
+}
+
+fn map_ip_protocol(value: u8) -> &'static str {
+    match value {
         1 => "ICMP",
         2 => "IGMP",
         6 => "TCP",
         17 => "UDP",
         41 => "ENCAP",
+        46 => "RSVP",
         47 => "GRE",
         50 => "ESP",
         51 => "AH",
@@ -618,35 +1425,101 @@ fn map_ip_protocol(value: u8) -> &'static str {
     }
 }
 
-fn describe_icmpv4(icmp_type: u8, icmp_code: u8) -> String {
+fn describe_icmpv4(icmp_type: u8, icmp_code: u8, message: &[u8]) -> String {
     match (icmp_type, icmp_code) {
-        (0, _) => "echo reply".to_string(),
+        (0, _) => format!("echo reply{}", echo_identifiers(message)),
         (3, 0) => "destination network unreachable".into(),
         (3, 1) => "destination host unreachable".into(),
         (3, 3) => "port unreachable".into(),
-        (5, 1) => "redirect host".into(),
-        (8, _) => "echo request".into(),
+        (5, 0..=3) => {
+            let kind = match icmp_code {
+                0 => "redirect network",
+                1 => "redirect host",
+                2 => "redirect TOS network",
+                _ => "redirect TOS host",
+            };
+            // The gateway address follows the type, code and checksum fields.
+            match message.get(4..8) {
+                Some(gateway) => format!(
+                    "{kind} via {}",
+                    Ipv4Addr::new(gateway[0], gateway[1], gateway[2], gateway[3])
+                ),
+                None => kind.into(),
+            }
+        }
+        (8, _) => format!("echo request{}", echo_identifiers(message)),
         (11, 0) => "time exceeded in transit".into(),
         (11, 1) => "fragment reassembly time exceeded".into(),
+        (13 | 14, _) => {
+            let kind = if icmp_type == 13 {
+                "timestamp request"
+            } else {
+                "timestamp reply"
+            };
+            // Milliseconds since midnight UT, after the identifier and sequence.
+            match message.get(8..12) {
+                Some(originate) => format!(
+                    "{kind} originate={}ms",
+                    u32::from_be_bytes([originate[0], originate[1], originate[2], originate[3]])
+                ),
+                None => kind.into(),
+            }
+        }
+        (17, _) => "address mask request".into(),
+        (18, _) => match message.get(8..12) {
+            Some(mask) => format!(
+                "address mask reply {}",
+                Ipv4Addr::new(mask[0], mask[1], mask[2], mask[3])
+            ),
+            None => "address mask reply".into(),
+        },
         _ => format!("type {icmp_type}, code {icmp_code}"),
     }
 }
 
-fn describe_icmpv6(icmp_type: u8, icmp_code: u8) -> String {
+fn describe_icmpv6(icmp_type: u8, icmp_code: u8, message: &[u8]) -> String {
     match (icmp_type, icmp_code) {
         (1, 0) => "destination unreachable".into(),
         (2, 0) => "packet too big".into(),
         (3, 0) => "time exceeded".into(),
-        (128, _) => "echo request".into(),
-        (129, _) => "echo reply".into(),
+        (128, _) => format!("echo request{}", echo_identifiers(message)),
+        (129, _) => format!("echo reply{}", echo_identifiers(message)),
         (133, _) => "router solicitation".into(),
         (134, _) => "router advertisement".into(),
         (135, _) => "neighbor solicitation".into(),
         (136, _) => "neighbor advertisement".into(),
+        (130, _) => "multicast listener query".into(),
+        (131, _) => "multicast listener report".into(),
+        (132, _) => "multicast listener done".into(),
+        (143, _) => "multicast listener report v2".into(),
         _ => format!("type {icmp_type}, code {icmp_code}"),
     }
 }
 
+/// Formats the identifier and sequence number that follow the type, code
+/// and checksum of an echo message, or nothing when they were not captured.
+fn echo_identifiers(message: &[u8]) -> String {
+    match message.get(4..8) {
+        Some(ids) => format!(
+            " id=0x{:04x} seq={}",
+            u16::from_be_bytes([ids[0], ids[1]]),
+            u16::from_be_bytes([ids[2], ids[3]])
+        ),
+        None => String::new(),
+    }
+}
+
+/// Distinguishes MLDv1 from MLDv2 messages. Queries share a type and differ
+/// only in length: MLDv2 queries (RFC 3810) are at least 28 bytes.
+fn mld_version(icmp_type: u8, message_len: usize) -> Option<&'static str> {
+    match icmp_type {
+        130 if message_len >= 28 => Some("MLDv2"),
+        130..=132 => Some("MLDv1"),
+        143 => Some("MLDv2"),
+        _ => None,
+    }
+}
+
 fn format_port(address: &str, port: u16) -> String {
     format!("{address}:{port}")
 }
@@ -666,12 +1539,14 @@ fn describe_nom_error(err: nom::Err<PcapError<&[u8]>>) -> String {
     }
 }
 
-fn process_raw_payload(data: &[u8]) -> PacketProcessingResult {
+fn process_raw_payload(data: &[u8], options: &ProcessOptions) -> PacketProcessingResult {
     if data.is_empty() {
         return PacketProcessingResult {
             packets: Vec::new(),
             warnings: Vec::new(),
             errors: Vec::new(),
+            capture_info: CaptureInfo::default(),
+            neighbor_cache: Vec::new(),
         };
     }
     let summary = if data.len() == 1 {
@@ -682,26 +1557,39 @@ fn process_raw_payload(data: &[u8]) -> PacketProcessingResult {
     let packet = create_packet(
         PacketMetadata {
             time: "0.000000".to_string(),
+            epoch_seconds: None,
             source: "upload".to_string(),
             destination: EM_DASH.to_string(),
             protocol: "RAW".to_string(),
             summary,
             length: data.len(),
+            frame_number: 1,
+            direction: None,
+            epoch_ns: None,
+            l3_offset: None,
+            interface: None,
+            interface_description: None,
+            comment: None,
             layers: None,
         },
         data,
+        options,
     );
     PacketProcessingResult {
         packets: vec![packet],
         warnings: Vec::new(),
         errors: Vec::new(),
+        capture_info: CaptureInfo::default(),
+        neighbor_cache: Vec::new(),
     }
 }
 
-fn process_pcap(data: &[u8]) -> Result<PacketProcessingResult, String> {
+fn process_pcap(data: &[u8], options: &ProcessOptions) -> Result<PacketProcessingResult, String> {
     let (header, mut offset) = parse_pcap_header(data)?;
     let mut packets = Vec::new();
     let mut warnings = Vec::new();
+    let mut trackers = CaptureTrackers::default();
+    let mut linktype_check = LinktypeCheck::new(header.linktype);
     let mut index = 0usize;
     while offset + 16 <= data.len() {
         let block = &data[offset..offset + 16];
@@ -710,216 +1598,2472 @@ fn process_pcap(data: &[u8]) -> Result<PacketProcessingResult, String> {
         let ts_frac = header.endianness.read_u32(&block[4..8]) as u64;
         let cap_len = header.endianness.read_u32(&block[8..12]) as usize;
         let orig_len = header.endianness.read_u32(&block[12..16]) as usize;
-        if offset + cap_len > data.len() {
-            warnings.push(format!(
-                "Packet {} header exceeds capture length",
-                index + 1
-            ));
-            break;
+        // A record running past the end is the last one of a file cut off
+        // mid-write; keep whatever bytes made it to disk.
+        let available = cap_len.min(data.len() - offset);
+        let file_truncated = available < cap_len;
+        let payload = &data[offset..offset + available];
+        offset += available;
+        linktype_check.observe(payload);
+        if !options.keeps_ip_version(frame_ip_version(header.linktype, payload)) {
+            index += 1;
+            continue;
         }
-        let payload = &data[offset..offset + cap_len];
-        offset += cap_len;
-        let mut analysis = analyze_payload(header.linktype, payload);
+        let mut analysis = trackers.analyze(header.linktype, payload, options, &mut warnings);
+        trackers.observe(&mut analysis, &mut warnings);
         if orig_len > cap_len {
             analysis.summary.push_str(" [truncated]");
-            warnings.push(format!(
-                "Packet {} truncated (captured {} of {} bytes)",
-                index + 1,
-                cap_len,
-                orig_len
-            ));
+            push_warning(
+                &mut warnings,
+                format!("Packet truncated to {cap_len} of {orig_len} bytes"),
+            );
+        }
+        if file_truncated {
+            analysis.summary.push_str(" [truncated file]");
+            push_warning(
+                &mut warnings,
+                format!(
+                    "Packet {} cut off by end of file ({} of {} captured bytes present)",
+                    index + 1,
+                    available,
+                    cap_len
+                ),
+            );
         }
         let timestamp_seconds = ts_sec as i64 + header.timezone_offset as i64;
         let metadata = PacketMetadata {
             time: format_timestamp(timestamp_seconds, ts_frac, header.resolution),
+            epoch_seconds: Some(timestamp_seconds),
+            epoch_ns: options
+                .ns_timestamps
+                .then(|| epoch_nanos(timestamp_seconds, ts_frac, header.resolution)),
             source: analysis.source,
             destination: analysis.destination,
             protocol: analysis.protocol,
             summary: analysis.summary,
-            length: cap_len,
+            length: available,
+            frame_number: index + 1,
+            direction: None,
+            l3_offset: network_layer(header.linktype, payload).map(|(offset, _)| offset),
+            interface: None,
+            interface_description: None,
+            comment: None,
             layers: Some(analysis.layers),
         };
-        packets.push(create_packet(metadata, payload));
+        packets.push(create_packet(metadata, payload, options));
         index += 1;
     }
+    linktype_check.finish(&mut warnings);
+    trackers.finish(&mut warnings);
     Ok(PacketProcessingResult {
         packets,
         warnings,
         errors: Vec::new(),
+        capture_info: CaptureInfo::default(),
+        neighbor_cache: trackers.neighbors.entries(),
     })
 }
 
-fn process_pcapng(data: &[u8]) -> Result<PacketProcessingResult, String> {
-    let mut slice = PcapNGSlice::from_slice(data).map_err(describe_nom_error)?;
+fn process_pcapng(data: &[u8], options: &ProcessOptions) -> Result<PacketProcessingResult, String> {
+    let mut sections = walk_pcapng(data, false, options)?;
+    Ok(sections.remove(0))
+}
+
+/// Walks every pcapng block, starting a fresh result (interface table and
+/// packet numbering included) at each Section Header Block when
+/// `split_sections` is set. Always returns at least one result.
+fn walk_pcapng(
+    data: &[u8],
+    split_sections: bool,
+    options: &ProcessOptions,
+) -> Result<Vec<PacketProcessingResult>, String> {
+    let slice = PcapNGSlice::from_slice(data).map_err(describe_nom_error)?;
+    let mut sections = Vec::new();
     let mut packets = Vec::new();
     let mut warnings = Vec::new();
     let mut interfaces: Vec<InterfaceInfo> = Vec::new();
+    let mut host_names = HashMap::new();
+    let mut drops = BTreeMap::new();
+    let mut trackers = CaptureTrackers::default();
     let mut packet_index = 0usize;
-    while let Some(block) = slice.next() {
+    let mut seen_section = false;
+    let mut capture_info = CaptureInfo::default();
+    let mut big_endian = false;
+    for block in slice {
         match block {
             Ok(pcap_parser::PcapBlockOwned::NG(block)) => match block {
-                Block::SectionHeader(_) => {
+                Block::SectionHeader(shb) => {
+                    report_interface_drops(&mut drops, &mut warnings);
+                    if split_sections && seen_section {
+                        trackers.finish(&mut warnings);
+                        apply_host_names(&mut packets, &host_names);
+                        host_names.clear();
+                        sections.push(PacketProcessingResult {
+                            packets: std::mem::take(&mut packets),
+                            warnings: std::mem::take(&mut warnings),
+                            errors: Vec::new(),
+                            capture_info: std::mem::take(&mut capture_info),
+                            neighbor_cache: trackers.neighbors.entries(),
+                        });
+                        packet_index = 0;
+                        trackers = CaptureTrackers::default();
+                    }
+                    seen_section = true;
                     interfaces.clear();
+                    big_endian = shb.big_endian();
+                    apply_section_options(&mut capture_info, &shb.options);
+                    collect_custom_options(&mut capture_info, &shb.options, big_endian);
+                }
+                Block::NameResolution(nrb) => collect_name_records(&nrb, &mut host_names),
+                Block::InterfaceStatistics(isb) => {
+                    let Some(info) = interfaces.get(isb.if_id as usize) else {
+                        push_warning(
+                            &mut warnings,
+                            format!(
+                                "Interface statistics reference unknown interface {}",
+                                isb.if_id
+                            ),
+                        );
+                        continue;
+                    };
+                    // Counters are cumulative, so only the latest block per
+                    // interface matters.
+                    if let (received, Some(dropped)) = interface_counters(&isb.options, big_endian)
+                    {
+                        drops.insert(isb.if_id, (info.name.clone(), received, dropped));
+                    }
                 }
                 Block::InterfaceDescription(idb) => {
-                    interfaces.push(InterfaceInfo::from_block(&idb));
+                    interfaces.push(InterfaceInfo::from_block(&idb, interfaces.len()));
+                    collect_custom_options(&mut capture_info, &idb.options, big_endian);
                 }
                 Block::EnhancedPacket(epb) => {
                     packet_index += 1;
-                    let Some(info) = interfaces.get(epb.if_id as usize).copied() else {
-                        warnings.push(format!(
-                            "Enhanced packet {} references unknown interface {}",
-                            packet_index, epb.if_id
-                        ));
+                    let Some(info) = interfaces.get(epb.if_id as usize).cloned() else {
+                        push_warning(
+                            &mut warnings,
+                            format!("Enhanced packet references unknown interface {}", epb.if_id),
+                        );
                         continue;
                     };
                     let payload = epb.packet_data();
+                    collect_custom_options(&mut capture_info, &epb.options, epb.big_endian());
+                    if !options.keeps_ip_version(frame_ip_version(info.linktype, payload)) {
+                        continue;
+                    }
                     let (ts_sec, ts_frac) = epb.decode_ts(info.ts_offset, info.ts_resolution);
-                    let mut analysis = analyze_payload(info.linktype, payload);
+                    let mut analysis =
+                        trackers.analyze(info.linktype, payload, options, &mut warnings);
+                    trackers.observe(&mut analysis, &mut warnings);
+                    let flags = parse_epb_flags(&epb.options, epb.big_endian());
+                    let direction = flags.as_ref().and_then(|flags| flags.direction);
+                    if let Some(flags) = &flags {
+                        let direction = flags.direction.map(|direction| match direction {
+                            Direction::Inbound => "inbound",
+                            Direction::Outbound => "outbound",
+                        });
+                        let fcs =
+                            (flags.fcs_length > 0).then(|| format!("fcs {}", flags.fcs_length));
+                        let note = [
+                            direction.map(str::to_string),
+                            flags.reception.map(str::to_string),
+                            fcs,
+                        ]
+                        .into_iter()
+                        .flatten()
+                        .collect::<Vec<_>>()
+                        .join(" ");
+                        if !note.is_empty() {
+                            analysis.summary.push_str(&format!(" [{note}]"));
+                        }
+                    }
                     if (epb.caplen as usize) < (epb.origlen as usize) {
                         analysis.summary.push_str(" [truncated]");
-                        warnings.push(format!(
-                            "Packet {} truncated (captured {} of {} bytes)",
-                            packet_index, epb.caplen, epb.origlen
-                        ));
+                        push_warning(
+                            &mut warnings,
+                            format!(
+                                "Packet truncated to {} of {} bytes",
+                                epb.caplen, epb.origlen
+                            ),
+                        );
                     }
                     let metadata = PacketMetadata {
                         time: format_timestamp(ts_sec as i64, ts_frac as u64, info.ts_resolution),
+                        epoch_seconds: Some(ts_sec as i64),
+                        epoch_ns: options.ns_timestamps.then(|| {
+                            epoch_nanos(ts_sec as i64, ts_frac as u64, info.ts_resolution)
+                        }),
                         source: analysis.source,
                         destination: analysis.destination,
                         protocol: analysis.protocol,
                         summary: analysis.summary,
                         length: payload.len(),
+                        frame_number: packet_index,
+                        direction,
+                        l3_offset: network_layer(info.linktype, payload).map(|(offset, _)| offset),
+                        interface: Some(info.name),
+                        interface_description: info.description,
+                        comment: packet_comment(&epb.options),
                         layers: Some(analysis.layers),
                     };
-                    packets.push(create_packet(metadata, payload));
+                    packets.push(create_packet(metadata, payload, options));
                 }
                 Block::SimplePacket(spb) => {
                     packet_index += 1;
-                    let info = interfaces.get(0).copied().unwrap_or(InterfaceInfo {
-                        linktype: 1,
-                        ts_offset: 0,
-                        ts_resolution: 1_000_000,
-                    });
+                    // Simple Packet Blocks implicitly belong to interface 0.
+                    let Some(info) = interfaces.first().cloned() else {
+                        push_warning(
+                            &mut warnings,
+                            "Simple packet appears before any interface description".to_string(),
+                        );
+                        continue;
+                    };
                     let payload = spb.packet_data();
-                    let mut analysis = analyze_payload(info.linktype, payload);
+                    if !options.keeps_ip_version(frame_ip_version(info.linktype, payload)) {
+                        continue;
+                    }
+                    let mut analysis =
+                        trackers.analyze(info.linktype, payload, options, &mut warnings);
+                    trackers.observe(&mut analysis, &mut warnings);
                     if (spb.origlen as usize) > payload.len() {
                         analysis.summary.push_str(" [truncated]");
-                        warnings.push(format!(
-                            "Packet {} truncated (captured {} of {} bytes)",
-                            packet_index,
-                            payload.len(),
-                            spb.origlen
-                        ));
+                        push_warning(
+                            &mut warnings,
+                            format!(
+                                "Packet truncated to {} of {} bytes",
+                                payload.len(),
+                                spb.origlen
+                            ),
+                        );
                     }
+                    // SPBs carry no timestamp at all, so the time is zero
+                    // rather than guessed from neighbouring blocks.
                     let metadata = PacketMetadata {
                         time: "0.000000".to_string(),
+                        epoch_seconds: None,
                         source: analysis.source,
                         destination: analysis.destination,
                         protocol: analysis.protocol,
                         summary: analysis.summary,
                         length: payload.len(),
+                        frame_number: packet_index,
+                        direction: None,
+                        epoch_ns: None,
+                        l3_offset: network_layer(info.linktype, payload).map(|(offset, _)| offset),
+                        interface: Some(info.name),
+                        interface_description: info.description,
+                        comment: None,
                         layers: Some(analysis.layers),
                     };
-                    packets.push(create_packet(metadata, payload));
+                    packets.push(create_packet(metadata, payload, options));
                 }
                 _ => {}
             },
             Ok(_) => {}
             Err(err) => {
-                warnings.push(describe_nom_error(err));
+                push_warning(&mut warnings, describe_nom_error(err));
                 break;
             }
         }
     }
-    Ok(PacketProcessingResult {
+    report_interface_drops(&mut drops, &mut warnings);
+    trackers.finish(&mut warnings);
+    apply_host_names(&mut packets, &host_names);
+    sections.push(PacketProcessingResult {
         packets,
         warnings,
         errors: Vec::new(),
-    })
+        capture_info,
+        neighbor_cache: trackers.neighbors.entries(),
+    });
+    Ok(sections)
 }
 
-#[wasm_bindgen]
-pub fn process_packet(data: &[u8]) -> String {
-    let result = if data.is_empty() {
-        PacketProcessingResult {
-            packets: Vec::new(),
-            warnings: vec!["Empty payload provided".to_string()],
-            errors: Vec::new(),
+/// Warns about every interface whose last statistics block in the section
+/// reported drops, then forgets the section's counters.
+fn report_interface_drops(
+    drops: &mut BTreeMap<u32, (String, Option<u64>, u64)>,
+    warnings: &mut Vec<String>,
+) {
+    for (name, received, dropped) in std::mem::take(drops).into_values() {
+        if dropped == 0 {
+            continue;
+        }
+        let total = received
+            .map(|received| format!(" of {received} received"))
+            .unwrap_or_default();
+        push_warning(
+            warnings,
+            format!("Interface {name} reported {dropped} dropped packets{total}"),
+        );
+    }
+}
+
+fn process_capture(data: &[u8], options: &ProcessOptions) -> PacketProcessingResult {
+    if data.is_empty() {
+        return empty_result();
+    }
+    let inflated;
+    let capture = if is_gzip(data) {
+        match gunzip(data) {
+            Ok(bytes) => {
+                inflated = bytes;
+                &inflated
+            }
+            Err(err) => {
+                let mut fallback = process_raw_payload(data, options);
+                fallback.errors.push(err);
+                return fallback;
+            }
         }
     } else {
-        match detect_format(data) {
-            CaptureFormat::Pcap => match process_pcap(data) {
-                Ok(result) => result,
-                Err(err) => {
-                    let mut fallback = process_raw_payload(data);
-                    fallback.errors.push(err);
-                    fallback
-                }
-            },
-            CaptureFormat::PcapNg => match process_pcapng(data) {
-                Ok(result) => result,
-                Err(err) => {
-                    let mut fallback = process_raw_payload(data);
-                    fallback.errors.push(err);
-                    fallback
-                }
-            },
-            CaptureFormat::Raw => process_raw_payload(data),
+        data
+    };
+    let format = detect_format(capture);
+    let recovered = match format {
+        CaptureFormat::Raw if options.try_all_formats => recover_damaged_capture(capture, options),
+        _ => None,
+    };
+    let parsed = match recovered {
+        Some(found) => Ok(found),
+        None => process_format(capture, format, options).map(|result| (format, result)),
+    };
+    let mut result = match parsed {
+        Ok((format, mut result)) => {
+            if !matches!(format, CaptureFormat::Raw) {
+                check_timestamp_range(&mut result);
+            }
+            result
+        }
+        Err(err) => {
+            let mut fallback = process_raw_payload(capture, options);
+            fallback.errors.push(err);
+            fallback
         }
     };
-    serialize_result(&result)
+    if options.first_per_flow {
+        result.packets = first_per_flow(std::mem::take(&mut result.packets));
+    }
+    if options.hash_file {
+        let digest = Sha256::digest(data);
+        result.capture_info.file_sha256 =
+            Some(digest.iter().map(|byte| format!("{byte:02x}")).collect());
+    }
+    result
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Re-parses input detected as raw as pcap and pcapng with the magic number
+/// restored, keeping whichever dissects the most packets. Returns `None`
+/// when neither recovers a single dissected packet.
+fn recover_damaged_capture(
+    data: &[u8],
+    options: &ProcessOptions,
+) -> Option<(CaptureFormat, PacketProcessingResult)> {
+    let mut pcap = data.to_vec();
+    // The major version is 2, so its byte order reveals the file's.
+    let pcap_magic = if data.get(4..6) == Some(&[0, 2]) {
+        0xA1B2_C3D4u32.to_be_bytes()
+    } else {
+        0xA1B2_C3D4u32.to_le_bytes()
+    };
+    pcap.get_mut(..4)?.copy_from_slice(&pcap_magic);
+    let mut pcapng = data.to_vec();
+    pcapng
+        .get_mut(..4)?
+        .copy_from_slice(&[0x0A, 0x0D, 0x0D, 0x0A]);
+    let (format, mut result, dissected) =
+        [(CaptureFormat::Pcap, pcap), (CaptureFormat::PcapNg, pcapng)]
+            .into_iter()
+            .filter_map(|(format, repaired)| {
+                let result = process_format(&repaired, format, options).ok()?;
+                let dissected = result
+                    .packets
+                    .iter()
+                    .filter(|packet| is_dissected(&packet.protocol))
+                    .count();
+                Some((format, result, dissected))
+            })
+            .max_by_key(|(_, _, dissected)| *dissected)?;
+    if dissected == 0 {
+        return None;
+    }
+    push_warning(
+        &mut result.warnings,
+        format!(
+            "Input has no valid capture header; recovered as {} (packets dissected: {dissected})",
+            format.name()
+        ),
+    );
+    Some((format, result))
+}
 
-    #[test]
-    fn builds_icmpv4_summary() {
-        let layers = DecodedLayers {
-            ipv4: Some(Ipv4Header {
-                source: "192.168.1.10".to_string(),
-                destination: "192.168.1.1".to_string(),
-                protocol: 1,
-                header_length: 20,
-                total_length: 84,
-                ttl: 64,
-            }),
-            icmp: Some(IcmpHeader {
-                icmp_type: 8,
-                icmp_code: 0,
-                description: "Echo Request".to_string(),
-                version: "ICMP".to_string(),
-            }),
-            ..Default::default()
-        };
+/// Whether a packet's protocol names something decoded rather than a
+/// placeholder for bytes no dissector understood.
+fn is_dissected(protocol: &str) -> bool {
+    !matches!(protocol, "RAW" | "Error") && !protocol.starts_with("LINKTYPE ")
+}
 
-        let summary = build_summary_from_layers(&layers, "fallback".to_string());
-        assert_eq!(summary, "ICMP 192.168.1.10 → 192.168.1.1 (Echo Request)");
+/// Earliest and latest capture times, as Unix seconds, that look like a
+/// correctly set clock.
+const PLAUSIBLE_EPOCHS: std::ops::Range<i64> = 946_684_800..4_102_444_800;
+
+/// Warns when the first or last packet is timestamped outside 2000–2100,
+/// which usually means the capturing host's clock was unset or the value
+/// overflowed.
+fn check_timestamp_range(result: &mut PacketProcessingResult) {
+    // Packets without a capture time, such as pcapng simple packets, are
+    // left out rather than read as 1970.
+    let mut timed = result
+        .packets
+        .iter()
+        .filter_map(|packet| packet.epoch_seconds);
+    let extremes = [timed.next(), timed.next_back()];
+    if extremes
+        .into_iter()
+        .flatten()
+        .any(|seconds| !PLAUSIBLE_EPOCHS.contains(&seconds))
+    {
+        push_warning(
+            &mut result.warnings,
+            "Capture contains timestamps before 2000 or after 2100, clock may be wrong".to_string(),
+        );
     }
+}
 
-    #[test]
-    fn builds_icmpv6_summary() {
-        let layers = DecodedLayers {
-            ipv6: Some(Ipv6Header {
-                source: "2001:db8::1".to_string(),
-                destination: "2001:db8::2".to_string(),
-                next_header: 58,
-                payload_length: 32,
-                hop_limit: 64,
-            }),
-            icmp: Some(IcmpHeader {
-                icmp_type: 128,
-                icmp_code: 0,
-                description: "Echo Request".to_string(),
-                version: "ICMPv6".to_string(),
-            }),
-            ..Default::default()
-        };
+fn process_format(
+    data: &[u8],
+    format: CaptureFormat,
+    options: &ProcessOptions,
+) -> Result<PacketProcessingResult, String> {
+    match format {
+        CaptureFormat::Pcap => process_pcap(data, options),
+        CaptureFormat::PcapNg => process_pcapng(data, options),
+        CaptureFormat::Raw => Ok(process_raw_payload(data, options)),
+    }
+}
 
-        let summary = build_summary_from_layers(&layers, "fallback".to_string());
-        assert_eq!(summary, "ICMPv6 2001:db8::1 → 2001:db8::2 (Echo Request)");
+fn empty_result() -> PacketProcessingResult {
+    PacketProcessingResult {
+        packets: Vec::new(),
+        warnings: vec!["Empty payload provided".to_string()],
+        errors: Vec::new(),
+        capture_info: CaptureInfo::default(),
+        neighbor_cache: Vec::new(),
+    }
+}
+
+#[wasm_bindgen]
+pub fn process_packet(data: &[u8]) -> String {
+    serialize_result(&process_capture(data, &ProcessOptions::default()))
+}
+
+/// Like `process_packet`, for a capture pasted as base64 text.
+#[wasm_bindgen]
+pub fn process_packet_base64(text: &str) -> String {
+    process_encoded(decode_base64(text))
+}
+
+/// Like `process_packet`, for a capture pasted as hex text. Whitespace and
+/// line breaks between digits are ignored.
+#[wasm_bindgen]
+pub fn process_packet_hex(text: &str) -> String {
+    process_encoded(decode_hex(text))
+}
+
+fn process_encoded(decoded: Result<Vec<u8>, String>) -> String {
+    match decoded {
+        Ok(data) => process_packet(&data),
+        Err(err) => serialize_result(&PacketProcessingResult {
+            packets: Vec::new(),
+            warnings: Vec::new(),
+            errors: vec![err],
+            capture_info: CaptureInfo::default(),
+            neighbor_cache: Vec::new(),
+        }),
+    }
+}
+
+/// Like `process_packet`, with behavior tuned by a JSON-encoded
+/// `ProcessOptions` object. Invalid options are reported in `errors` and
+/// the defaults are used instead.
+#[wasm_bindgen]
+pub fn process_packet_with_options(data: &[u8], options: &str) -> String {
+    match ProcessOptions::from_json(options) {
+        Ok(options) => serialize_result(&process_capture(data, &options)),
+        Err(err) => {
+            let mut result = process_capture(data, &ProcessOptions::default());
+            result.errors.push(err);
+            serialize_result(&result)
+        }
+    }
+}
+
+/// Like `process_packet`, encoded as MessagePack with named fields so the
+/// schema matches the JSON output. Returns an empty buffer if encoding fails.
+#[cfg(feature = "msgpack")]
+#[wasm_bindgen]
+pub fn process_packet_msgpack(data: &[u8]) -> Vec<u8> {
+    rmp_serde::to_vec_named(&process_capture(data, &ProcessOptions::default())).unwrap_or_default()
+}
+
+/// Parses `data` as the given format instead of detecting it, for inputs
+/// that `detect_format` misidentifies. If the forced parser fails, the input
+/// is returned as a raw payload with an error naming the forced format.
+#[wasm_bindgen]
+pub fn process_packet_as(data: &[u8], format: CaptureFormat) -> String {
+    if data.is_empty() {
+        return serialize_result(&empty_result());
+    }
+    let options = ProcessOptions::default();
+    let result = process_format(data, format, &options).unwrap_or_else(|err| {
+        let mut fallback = process_raw_payload(data, &options);
+        fallback
+            .errors
+            .push(format!("Forced {} parsing failed: {err}", format.name()));
+        fallback
+    });
+    serialize_result(&result)
+}
+
+/// Processes a pcapng file made of several concatenated sections, returning
+/// one serialized result per Section Header Block. Inputs that are not
+/// pcapng yield a single result, exactly as `process_packet` would.
+#[wasm_bindgen]
+pub fn split_sections(data: &[u8]) -> Vec<String> {
+    if data.is_empty() || !matches!(detect_format(data), CaptureFormat::PcapNg) {
+        return vec![process_packet(data)];
+    }
+    let options = ProcessOptions::default();
+    match walk_pcapng(data, true, &options) {
+        Ok(sections) => sections.iter().map(serialize_result).collect(),
+        Err(err) => {
+            let mut fallback = process_raw_payload(data, &options);
+            fallback.errors.push(err);
+            vec![serialize_result(&fallback)]
+        }
+    }
+}
+
+/// Like `process_packet`, annotating IP sources and destinations with the
+/// string `geo_fn(ip)` returns, e.g. `8.8.8.8:53 (US)`. Each distinct address
+/// is looked up once per call; addresses for which the callback throws or
+/// returns a non-string stay unannotated.
+#[wasm_bindgen]
+pub fn process_packet_with_geo(data: &[u8], geo_fn: &Function) -> String {
+    let mut result = process_capture(data, &ProcessOptions::default());
+    annotate_locations(&mut result, |ip| {
+        geo_fn
+            .call1(&JsValue::NULL, &JsValue::from_str(ip))
+            .ok()?
+            .as_string()
+    });
+    serialize_result(&result)
+}
+
+/// Returns a JSON array with one `{time, src, dst, qname, qtype, rcode,
+/// answers}` record per DNS message in the capture, skipping other packets.
+#[wasm_bindgen]
+pub fn extract_dns(data: &[u8]) -> String {
+    let result = process_capture(data, &ProcessOptions::default());
+    let records: Vec<DnsRecord> = result
+        .packets
+        .into_iter()
+        .filter_map(|packet| {
+            let dns = packet.layers?.dns?;
+            Some(DnsRecord {
+                time: packet.time,
+                src: packet.source,
+                dst: packet.destination,
+                qname: dns.qname,
+                qtype: dns.qtype,
+                rcode: dns.rcode,
+                answers: dns.answers,
+            })
+        })
+        .collect();
+    serde_json::to_string(&records).unwrap_or_else(|_| "[]".into())
+}
+
+/// Reassembles the capture's TCP flows and returns a JSON array with one
+/// `{client, server, method, uri, status, content_type, body_length,
+/// truncated}` record per HTTP/1.x response. With `include_bodies` each
+/// record also carries the de-chunked `body` as base64.
+#[wasm_bindgen]
+pub fn extract_http_objects(data: &[u8], include_bodies: bool) -> String {
+    let result = process_capture(data, &ProcessOptions::default());
+    let objects = extract_objects(&result.packets, include_bodies);
+    serde_json::to_string(&objects).unwrap_or_else(|_| "[]".into())
+}
+
+/// Lists every pcapng block with its type, declared length, and interface id
+/// (for packet blocks) without dissecting payloads, to diagnose corrupt
+/// files. Walking stops at the first unparseable block, which is reported in
+/// `errors`.
+#[wasm_bindgen]
+pub fn dump_blocks(data: &[u8]) -> String {
+    let mut dump = BlockDump {
+        blocks: Vec::new(),
+        errors: Vec::new(),
+    };
+    match PcapNGSlice::from_slice(data) {
+        Ok(slice) => {
+            let mut offset = 0usize;
+            for block in slice {
+                match block {
+                    Ok(pcap_parser::PcapBlockOwned::NG(block)) => {
+                        let record = describe_block(&block, offset);
+                        offset += record.length as usize;
+                        dump.blocks.push(record);
+                    }
+                    Ok(_) => {}
+                    Err(err) => {
+                        dump.errors.push(format!(
+                            "Block at offset {offset}: {}",
+                            describe_nom_error(err)
+                        ));
+                        break;
+                    }
+                }
+            }
+        }
+        Err(err) => dump.errors.push(describe_nom_error(err)),
+    }
+    serde_json::to_string(&dump).unwrap_or_else(|_| "{\"blocks\":[],\"errors\":[]}".into())
+}
+
+/// Dissects headerless captures made of back-to-back records, each a 4-byte
+/// big-endian length followed by that many frame bytes. Every frame is
+/// analyzed as `linktype`; when none is given Ethernet is assumed and a
+/// warning says so.
+#[wasm_bindgen]
+pub fn process_raw_framed(data: &[u8], linktype: Option<u32>) -> String {
+    serialize_result(&walk_raw_framed(data, linktype, &ProcessOptions::default()))
+}
+
+fn walk_raw_framed(
+    data: &[u8],
+    linktype: Option<u32>,
+    options: &ProcessOptions,
+) -> PacketProcessingResult {
+    let mut packets = Vec::new();
+    let mut warnings = Vec::new();
+    let linktype = linktype.unwrap_or_else(|| {
+        push_warning(
+            &mut warnings,
+            "No linktype given for raw records, assuming Ethernet (1)".to_string(),
+        );
+        1
+    });
+    let mut trackers = CaptureTrackers::default();
+    let mut offset = 0usize;
+    let mut frame_number = 0;
+    while offset < data.len() {
+        frame_number += 1;
+        let Some(prefix) = data.get(offset..offset + 4) else {
+            push_warning(
+                &mut warnings,
+                format!("Record {frame_number} has a truncated length prefix"),
+            );
+            break;
+        };
+        let length = u32::from_be_bytes([prefix[0], prefix[1], prefix[2], prefix[3]]) as usize;
+        offset += 4;
+        // The length is file-supplied, so the sum can overflow a 32-bit usize.
+        let end = offset.checked_add(length);
+        let Some(payload) = end.and_then(|end| data.get(offset..end)) else {
+            push_warning(
+                &mut warnings,
+                format!("Record {frame_number} length exceeds remaining data"),
+            );
+            break;
+        };
+        offset += payload.len();
+        if !options.keeps_ip_version(frame_ip_version(linktype, payload)) {
+            continue;
+        }
+        let mut analysis = trackers.analyze(linktype, payload, options, &mut warnings);
+        trackers.observe(&mut analysis, &mut warnings);
+        packets.push(untimed_packet(
+            linktype,
+            analysis,
+            payload,
+            frame_number,
+            options,
+        ));
+    }
+    trackers.finish(&mut warnings);
+    PacketProcessingResult {
+        packets,
+        warnings,
+        errors: Vec::new(),
+        capture_info: CaptureInfo::default(),
+        neighbor_cache: trackers.neighbors.entries(),
+    }
+}
+
+/// Identifies a capture from its header and first packet only, returning
+/// JSON `{format, linktype, linktype_name, first_protocol}`. Empty input
+/// reports the format `empty`.
+#[wasm_bindgen]
+pub fn classify(data: &[u8]) -> String {
+    serialize_result(&classify_capture(data))
+}
+
+fn classify_capture(data: &[u8]) -> Classification {
+    if data.is_empty() {
+        return Classification {
+            format: "empty".to_string(),
+            linktype: None,
+            linktype_name: None,
+            first_protocol: None,
+        };
+    }
+    let format = detect_format(data);
+    let (linktype, first_protocol) = match format {
+        CaptureFormat::Pcap => classify_pcap(data),
+        CaptureFormat::PcapNg => classify_pcapng(data),
+        CaptureFormat::Raw => (None, None),
+    };
+    Classification {
+        format: format.name().to_string(),
+        linktype,
+        linktype_name: linktype.map(|linktype| linktype_name(linktype).to_string()),
+        first_protocol,
+    }
+}
+
+fn first_protocol(linktype: Option<u32>, payload: &[u8]) -> Option<String> {
+    let analysis = analyze_payload(
+        linktype?,
+        payload,
+        &ProcessOptions::default(),
+        &mut Vec::new(),
+    );
+    Some(analysis.protocol)
+}
+
+/// Reads the linktype and dissects the first record of a pcap file.
+fn classify_pcap(data: &[u8]) -> (Option<u32>, Option<String>) {
+    let Ok((header, offset)) = parse_pcap_header(data) else {
+        return (None, None);
+    };
+    let linktype = Some(header.linktype);
+    let protocol = data
+        .get(offset..offset + 16)
+        .and_then(|record| {
+            let cap_len = header.endianness.read_u32(&record[8..12]) as usize;
+            // An overflowing capture length leaves the record unclassified.
+            let start = offset + 16;
+            data.get(start..start.checked_add(cap_len)?)
+        })
+        .and_then(|payload| first_protocol(linktype, payload));
+    (linktype, protocol)
+}
+
+/// Reads pcapng blocks up to the first packet, dissecting it with its
+/// interface's linktype.
+fn classify_pcapng(data: &[u8]) -> (Option<u32>, Option<String>) {
+    let Ok(slice) = PcapNGSlice::from_slice(data) else {
+        return (None, None);
+    };
+    let mut linktypes = Vec::new();
+    for block in slice {
+        let Ok(pcap_parser::PcapBlockOwned::NG(block)) = block else {
+            break;
+        };
+        match block {
+            Block::InterfaceDescription(idb) => linktypes.push(idb.linktype.0 as u32),
+            Block::EnhancedPacket(epb) => {
+                let linktype = linktypes.get(epb.if_id as usize).copied();
+                return (linktype, first_protocol(linktype, epb.packet_data()));
+            }
+            Block::SimplePacket(spb) => {
+                let linktype = linktypes.first().copied();
+                return (linktype, first_protocol(linktype, spb.packet_data()));
+            }
+            _ => {}
+        }
+    }
+    (linktypes.first().copied(), None)
+}
+
+/// Dissects `data` as a single Ethernet frame, skipping format detection, for
+/// frames copied out of a hex dump.
+#[wasm_bindgen]
+pub fn dissect_ethernet(data: &[u8]) -> String {
+    let options = ProcessOptions::default();
+    let analysis = analyze_ethernet_frame(data, &options, Decap::new(&options));
+    serialize_result(&PacketProcessingResult {
+        packets: vec![untimed_packet(1, analysis, data, 1, &options)],
+        warnings: Vec::new(),
+        errors: Vec::new(),
+        capture_info: CaptureInfo::default(),
+        neighbor_cache: Vec::new(),
+    })
+}
+
+/// Wraps an analysis in a packet for inputs that carry no timestamps.
+fn untimed_packet(
+    linktype: u32,
+    analysis: PacketAnalysis,
+    payload: &[u8],
+    frame_number: usize,
+    options: &ProcessOptions,
+) -> Packet {
+    let metadata = PacketMetadata {
+        time: "0.000000".to_string(),
+        epoch_seconds: None,
+        source: analysis.source,
+        destination: analysis.destination,
+        protocol: analysis.protocol,
+        summary: analysis.summary,
+        length: payload.len(),
+        frame_number,
+        direction: None,
+        epoch_ns: None,
+        l3_offset: network_layer(linktype, payload).map(|(offset, _)| offset),
+        interface: None,
+        interface_description: None,
+        comment: None,
+        layers: Some(analysis.layers),
+    };
+    create_packet(metadata, payload, options)
+}
+
+/// Runs each input through the `process_packet` pipeline and returns a JSON
+/// array with one result per input, in order. Failures stay confined to the
+/// `errors` of the affected input's result.
+#[wasm_bindgen]
+pub fn process_batch(inputs: Vec<Uint8Array>) -> String {
+    let inputs: Vec<Vec<u8>> = inputs.iter().map(Uint8Array::to_vec).collect();
+    process_batch_inputs(&inputs)
+}
+
+fn process_batch_inputs(inputs: &[Vec<u8>]) -> String {
+    let options = ProcessOptions::default();
+    let results: Vec<PacketProcessingResult> = inputs
+        .iter()
+        .map(|data| process_capture(data, &options))
+        .collect();
+    serde_json::to_string(&results).unwrap_or_else(|_| "[]".into())
+}
+
+/// Like `process_packet`, but emits each packet's summary once as a flat
+/// object instead of repeating it inside a JSON-encoded `info` string.
+/// See `CompactPacket` for the emitted shape.
+#[wasm_bindgen]
+pub fn process_packet_compact(data: &[u8]) -> String {
+    serialize_result(&CompactProcessingResult::from(process_capture(
+        data,
+        &ProcessOptions::default(),
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_pcap(linktype: u32, records: &[&[u8]]) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend_from_slice(&0xA1B2_C3D4u32.to_le_bytes());
+        data.extend_from_slice(&2u16.to_le_bytes());
+        data.extend_from_slice(&4u16.to_le_bytes());
+        data.extend_from_slice(&[0; 8]);
+        data.extend_from_slice(&65_535u32.to_le_bytes());
+        data.extend_from_slice(&linktype.to_le_bytes());
+        for (index, record) in records.iter().enumerate() {
+            data.extend_from_slice(&(index as u32).to_le_bytes());
+            data.extend_from_slice(&0u32.to_le_bytes());
+            data.extend_from_slice(&(record.len() as u32).to_le_bytes());
+            data.extend_from_slice(&(record.len() as u32).to_le_bytes());
+            data.extend_from_slice(record);
+        }
+        data
+    }
+
+    fn pcapng_options(options: &[(u16, &[u8])]) -> Vec<u8> {
+        let mut encoded = Vec::new();
+        for (code, value) in options {
+            encoded.extend_from_slice(&code.to_le_bytes());
+            encoded.extend_from_slice(&(value.len() as u16).to_le_bytes());
+            encoded.extend_from_slice(value);
+            encoded.resize(encoded.len().next_multiple_of(4), 0);
+        }
+        if !options.is_empty() {
+            encoded.extend_from_slice(&[0; 4]);
+        }
+        encoded
+    }
+
+    fn pcapng_block(block_type: u32, body: &[u8]) -> Vec<u8> {
+        let padded = body.len().next_multiple_of(4);
+        let total = (padded + 12) as u32;
+        let mut block = Vec::new();
+        block.extend_from_slice(&block_type.to_le_bytes());
+        block.extend_from_slice(&total.to_le_bytes());
+        block.extend_from_slice(body);
+        block.resize(8 + padded, 0);
+        block.extend_from_slice(&total.to_le_bytes());
+        block
+    }
+
+    fn pcapng_shb(options: &[(u16, &[u8])]) -> Vec<u8> {
+        let mut body = Vec::new();
+        body.extend_from_slice(&0x1A2B_3C4Du32.to_le_bytes());
+        body.extend_from_slice(&1u16.to_le_bytes());
+        body.extend_from_slice(&0u16.to_le_bytes());
+        body.extend_from_slice(&(-1i64).to_le_bytes());
+        body.extend_from_slice(&pcapng_options(options));
+        pcapng_block(0x0A0D_0D0A, &body)
+    }
+
+    fn pcapng_idb(linktype: u16, options: &[(u16, &[u8])]) -> Vec<u8> {
+        let mut body = Vec::new();
+        body.extend_from_slice(&linktype.to_le_bytes());
+        body.extend_from_slice(&0u16.to_le_bytes());
+        body.extend_from_slice(&65_535u32.to_le_bytes());
+        body.extend_from_slice(&pcapng_options(options));
+        pcapng_block(1, &body)
+    }
+
+    fn pcapng_epb(if_id: u32, data: &[u8], options: &[(u16, &[u8])]) -> Vec<u8> {
+        let mut body = Vec::new();
+        body.extend_from_slice(&if_id.to_le_bytes());
+        body.extend_from_slice(&0u32.to_le_bytes());
+        body.extend_from_slice(&1_000_000u32.to_le_bytes());
+        body.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        body.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        body.extend_from_slice(data);
+        body.resize(body.len().next_multiple_of(4), 0);
+        body.extend_from_slice(&pcapng_options(options));
+        pcapng_block(6, &body)
+    }
+
+    fn pcapng_spb(data: &[u8]) -> Vec<u8> {
+        let mut body = (data.len() as u32).to_le_bytes().to_vec();
+        body.extend_from_slice(data);
+        body.resize(body.len().next_multiple_of(4), 0);
+        pcapng_block(3, &body)
+    }
+
+    fn ipv4_packet(protocol: u8, transport: &[u8]) -> Vec<u8> {
+        let mut packet = vec![0u8; 20];
+        packet[0] = 0x45;
+        packet[2..4].copy_from_slice(&((20 + transport.len()) as u16).to_be_bytes());
+        packet[8] = 64;
+        packet[9] = protocol;
+        packet[12..16].copy_from_slice(&[10, 0, 0, 1]);
+        packet[16..20].copy_from_slice(&[10, 0, 0, 2]);
+        packet.extend_from_slice(transport);
+        packet
+    }
+
+    fn udp_segment(src_port: u16, dst_port: u16, data: &[u8]) -> Vec<u8> {
+        let mut segment = Vec::new();
+        segment.extend_from_slice(&src_port.to_be_bytes());
+        segment.extend_from_slice(&dst_port.to_be_bytes());
+        segment.extend_from_slice(&((8 + data.len()) as u16).to_be_bytes());
+        segment.extend_from_slice(&[0, 0]);
+        segment.extend_from_slice(data);
+        segment
+    }
+
+    fn tcp_segment(src_port: u16, dst_port: u16, flags: u8, data: &[u8]) -> Vec<u8> {
+        let mut segment = vec![0u8; 20];
+        segment[0..2].copy_from_slice(&src_port.to_be_bytes());
+        segment[2..4].copy_from_slice(&dst_port.to_be_bytes());
+        segment[12] = 5 << 4;
+        segment[13] = flags;
+        segment.extend_from_slice(data);
+        segment
+    }
+
+    fn packet_infos(output: &str) -> Vec<serde_json::Value> {
+        let result: serde_json::Value = serde_json::from_str(output).unwrap();
+        result["packets"].as_array().unwrap().clone()
+    }
+
+    #[test]
+    fn serializes_packets_flat() {
+        let data = build_pcap(101, &[&ipv4_packet(17, &udp_segment(5353, 53, &[]))]);
+        let packet = &packet_infos(&process_packet(&data))[0];
+        assert_eq!(packet["info"], "UDP 10.0.0.1:5353 → 10.0.0.2:53 (dns)");
+        assert_eq!(packet["summary"], packet["info"]);
+        assert_eq!(packet["source"], "10.0.0.1:5353");
+        assert_eq!(packet["frame_number"], 1);
+        assert_eq!(
+            packet["hex_preview"].as_str().map(|hex| &hex[..5]),
+            Some("45 00")
+        );
+        assert!(packet.get("src").is_none());
+    }
+
+    #[test]
+    fn numbers_frames_in_capture_order() {
+        let data = build_pcap(147, &[&[1, 2, 3], &[4, 5, 6]]);
+        let infos = packet_infos(&process_packet(&data));
+        assert_eq!(infos[0]["frame_number"], 1);
+        assert_eq!(infos[1]["frame_number"], 2);
+    }
+
+    #[test]
+    fn builds_icmpv4_summary() {
+        let layers = DecodedLayers {
+            ipv4: Some(Ipv4Header {
+                source: "192.168.1.10".to_string(),
+                destination: "192.168.1.1".to_string(),
+                protocol: 1,
+                header_length: 20,
+                total_length: 84,
+                ttl: 64,
+                checksum_valid: true,
+            }),
+            icmp: Some(IcmpHeader {
+                icmp_type: 8,
+                icmp_code: 0,
+                description: "Echo Request".to_string(),
+                version: "ICMP".to_string(),
+            }),
+            ..Default::default()
+        };
+
+        let summary = build_summary_from_layers(&layers, "fallback".to_string());
+        assert_eq!(summary, "ICMP 192.168.1.10 → 192.168.1.1 (Echo Request)");
+    }
+
+    #[test]
+    fn builds_icmpv6_summary() {
+        let layers = DecodedLayers {
+            ipv6: Some(Ipv6Header {
+                source: "2001:db8::1".to_string(),
+                destination: "2001:db8::2".to_string(),
+                next_header: 58,
+                payload_length: 32,
+                hop_limit: 64,
+            }),
+            icmp: Some(IcmpHeader {
+                icmp_type: 128,
+                icmp_code: 0,
+                description: "Echo Request".to_string(),
+                version: "ICMPv6".to_string(),
+            }),
+            ..Default::default()
+        };
+
+        let summary = build_summary_from_layers(&layers, "fallback".to_string());
+        assert_eq!(summary, "ICMPv6 2001:db8::1 → 2001:db8::2 (Echo Request)");
+    }
+
+    #[test]
+    fn describes_icmpv4_redirect_gateway() {
+        let message = [5, 1, 0, 0, 10, 0, 0, 1];
+        assert_eq!(
+            describe_icmpv4(5, 1, &message),
+            "redirect host via 10.0.0.1"
+        );
+        assert_eq!(describe_icmpv4(5, 1, &message[..6]), "redirect host");
+    }
+
+    #[test]
+    fn reports_echo_identifier_and_sequence() {
+        let request = [8, 0, 0, 0, 0x12, 0x34, 0, 7];
+        assert_eq!(
+            describe_icmpv4(8, 0, &request),
+            "echo request id=0x1234 seq=7"
+        );
+        assert_eq!(describe_icmpv4(8, 0, &request[..6]), "echo request");
+        let reply = [129, 0, 0, 0, 0xAB, 0xCD, 1, 0];
+        assert_eq!(
+            describe_icmpv6(129, 0, &reply),
+            "echo reply id=0xabcd seq=256"
+        );
+    }
+
+    #[test]
+    fn decodes_icmpv4_timestamp_and_mask() {
+        let timestamp = [13, 0, 0, 0, 0, 1, 0, 1, 0, 0x36, 0xEE, 0x80, 0, 0, 0, 0];
+        assert_eq!(
+            describe_icmpv4(13, 0, &timestamp),
+            "timestamp request originate=3600000ms"
+        );
+        assert_eq!(
+            describe_icmpv4(13, 0, &timestamp[..10]),
+            "timestamp request"
+        );
+        let mask = [18, 0, 0, 0, 0, 1, 0, 1, 255, 255, 255, 0];
+        assert_eq!(
+            describe_icmpv4(18, 0, &mask),
+            "address mask reply 255.255.255.0"
+        );
+        assert_eq!(describe_icmpv4(18, 0, &mask[..8]), "address mask reply");
+    }
+
+    #[test]
+    fn recovers_capture_with_damaged_magic_when_enabled() {
+        let mut data = build_pcap(101, &[&ipv4_packet(17, &udp_segment(5000, 5001, b"hi"))]);
+        data[..4].copy_from_slice(&[0xFF; 4]);
+        let plain = process_capture(&data, &ProcessOptions::default());
+        assert_eq!(plain.packets[0].protocol, "RAW");
+        let options = ProcessOptions {
+            try_all_formats: true,
+            ..ProcessOptions::default()
+        };
+        let result = process_capture(&data, &options);
+        assert_eq!(result.packets.len(), 1);
+        assert_eq!(result.packets[0].protocol, "UDP");
+        assert!(
+            result.warnings.contains(
+                &"Input has no valid capture header; recovered as PCAP (packets dissected: 1)"
+                    .to_string()
+            )
+        );
+        // Genuinely raw input still falls back to a single raw payload.
+        let raw = process_capture(&[0xFF; 40], &options);
+        assert_eq!(raw.packets[0].protocol, "RAW");
+    }
+
+    #[test]
+    fn attaches_epb_comments_to_packets() {
+        let mut data = pcapng_shb(&[]);
+        data.extend(pcapng_idb(147, &[]));
+        data.extend(pcapng_epb(0, &[1], &[(1, b"login"), (1, b"retry")]));
+        data.extend(pcapng_epb(0, &[2], &[]));
+        let infos = packet_infos(&process_packet(&data));
+        assert_eq!(infos[0]["comment"], "login\nretry");
+        assert!(infos[1]["comment"].is_null());
+    }
+
+    #[test]
+    fn surfaces_epb_direction_flags() {
+        let flags = 0b0110u32.to_le_bytes();
+        let mut data = pcapng_shb(&[]);
+        data.extend(pcapng_idb(147, &[]));
+        data.extend(pcapng_epb(0, &[1, 2, 3], &[(2, &flags)]));
+        let infos = packet_infos(&process_packet(&data));
+        assert_eq!(infos[0]["direction"], "outbound");
+        assert!(
+            infos[0]["summary"]
+                .as_str()
+                .unwrap()
+                .ends_with("[outbound unicast]")
+        );
+    }
+
+    #[test]
+    fn splits_concatenated_sections() {
+        let mut data = Vec::new();
+        for _ in 0..2 {
+            data.extend(pcapng_shb(&[]));
+            data.extend(pcapng_idb(147, &[]));
+            data.extend(pcapng_epb(0, &[1, 2, 3], &[]));
+        }
+        let sections = split_sections(&data);
+        assert_eq!(sections.len(), 2);
+        for section in &sections {
+            let infos = packet_infos(section);
+            assert_eq!(infos.len(), 1);
+            assert_eq!(infos[0]["frame_number"], 1);
+        }
+        assert_eq!(packet_infos(&process_packet(&data))[1]["frame_number"], 2);
+    }
+
+    #[test]
+    fn options_set_preview_length() {
+        let data = build_pcap(147, &[&[0x41u8; 40]]);
+        let infos = packet_infos(&process_packet_with_options(&data, "{\"preview_len\": 4}"));
+        assert_eq!(infos[0]["hex_preview"], "41 41 41 41 …");
+        assert_eq!(infos[0]["ascii_preview"], "AAAA…");
+
+        let infos = packet_infos(&process_packet_with_options(&data, "{\"preview_len\": 0}"));
+        assert_eq!(infos[0]["hex_preview"], "");
+        assert_eq!(infos[0]["ascii_preview"], "");
+    }
+
+    #[test]
+    fn marks_high_entropy_frames_when_enabled() {
+        let every_byte: Vec<u8> = (0..=255u8).cycle().take(1024).collect();
+        let data = build_pcap(147, &[&every_byte, &[0u8; 256], &every_byte[..255]]);
+        let options = ProcessOptions {
+            compute_entropy: true,
+            ..ProcessOptions::default()
+        };
+        let result = process_capture(&data, &options);
+        assert!(result.packets[0].info.summary.ends_with(" [high entropy]"));
+        assert_eq!(result.packets[1].info.entropy, Some(0.0));
+        assert!(!result.packets[1].info.summary.contains("[high entropy]"));
+        assert_eq!(result.packets[2].info.entropy, None);
+
+        let result = process_capture(&data, &ProcessOptions::default());
+        assert_eq!(result.packets[0].info.entropy, None);
+    }
+
+    #[test]
+    fn accepts_hex_and_base64_captures() {
+        let data = build_pcap(101, &[&ipv4_packet(17, &udp_segment(5353, 53, &[]))]);
+        let hex: String = data.iter().map(|byte| format!("{byte:02x} ")).collect();
+        assert_eq!(process_packet_hex(&hex), process_packet(&data));
+
+        let base64 = "1MOyoQIABAAAAAAAAAAAAP//AAB9AAAA";
+        let decoded = [
+            0xD4, 0xC3, 0xB2, 0xA1, 2, 0, 4, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0xFF, 0xFF, 0, 0, 0x7D, 0,
+            0, 0,
+        ];
+        assert_eq!(process_packet_base64(base64), process_packet(&decoded));
+        assert_eq!(
+            process_packet_base64(&crate::encoding::encode_base64(&data)),
+            process_packet(&data)
+        );
+
+        let result: serde_json::Value =
+            serde_json::from_str(&process_packet_hex("not hex")).unwrap();
+        assert_eq!(result["packets"].as_array().map(Vec::len), Some(0));
+        assert!(
+            result["errors"][0]
+                .as_str()
+                .unwrap()
+                .starts_with("Invalid hex")
+        );
+    }
+
+    #[test]
+    fn options_select_tail_preview() {
+        let data = build_pcap(147, &[&[0u8; 40]]);
+        let output = process_packet_with_options(&data, "{\"preview_from_end\": true}");
+        let infos = packet_infos(&output);
+        assert!(
+            infos[0]["hex_preview"]
+                .as_str()
+                .unwrap()
+                .starts_with("… 00")
+        );
+
+        let result: serde_json::Value =
+            serde_json::from_str(&process_packet_with_options(&data, "not json")).unwrap();
+        assert_eq!(result["packets"].as_array().unwrap().len(), 1);
+        assert_eq!(result["errors"].as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn reports_section_header_provenance() {
+        let mut data = pcapng_shb(&[(3, b"Linux 6.1"), (4, b"tcpdump 4.99")]);
+        data.extend(pcapng_idb(1, &[]));
+        let result: serde_json::Value = serde_json::from_str(&process_packet(&data)).unwrap();
+        assert_eq!(
+            result["capture_info"],
+            serde_json::json!({"os": "Linux 6.1", "app": "tcpdump 4.99"})
+        );
+    }
+
+    #[test]
+    fn forced_format_reports_failure() {
+        let output = process_packet_as(&[1, 2, 3, 4], CaptureFormat::Pcap);
+        let result: serde_json::Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(result["packets"][0]["protocol"], "RAW");
+        assert!(
+            result["errors"][0]
+                .as_str()
+                .unwrap()
+                .starts_with("Forced PCAP parsing failed")
+        );
+
+        let pcap = build_pcap(147, &[&[1, 2, 3]]);
+        let forced: serde_json::Value =
+            serde_json::from_str(&process_packet_as(&pcap, CaptureFormat::Raw)).unwrap();
+        assert_eq!(forced["packets"][0]["protocol"], "RAW");
+    }
+
+    #[test]
+    fn batch_keeps_inputs_independent() {
+        let pcap = build_pcap(147, &[&[1, 2, 3]]);
+        let output = process_batch_inputs(&[pcap, Vec::new(), vec![0x0A, 0x0D, 0x0D, 0x0A]]);
+        let results: serde_json::Value = serde_json::from_str(&output).unwrap();
+        let results = results.as_array().unwrap();
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0]["packets"].as_array().unwrap().len(), 1);
+        assert!(results[1]["packets"].as_array().unwrap().is_empty());
+        assert!(!results[2]["errors"].as_array().unwrap().is_empty());
+        assert!(results[0]["errors"].as_array().unwrap().is_empty());
+    }
+
+    #[test]
+    fn dumps_block_skeleton() {
+        let mut data = pcapng_shb(&[]);
+        data.extend_from_slice(&pcapng_idb(1, &[]));
+        data.extend_from_slice(&pcapng_epb(0, &[1, 2, 3], &[]));
+        data.extend_from_slice(&pcapng_block(0x1234, &[0; 4]));
+        let dump: serde_json::Value = serde_json::from_str(&dump_blocks(&data)).unwrap();
+        let blocks = dump["blocks"].as_array().unwrap();
+        let names: Vec<&str> = blocks.iter().map(|b| b["name"].as_str().unwrap()).collect();
+        assert_eq!(names, ["SHB", "IDB", "EPB", "0x00001234"]);
+        assert_eq!(blocks[2]["interface_id"], 0);
+        assert_eq!(blocks[2]["offset"], 48);
+        assert_eq!(blocks[3]["block_type"], 0x1234);
+        assert!(dump["errors"].as_array().unwrap().is_empty());
+    }
+
+    #[test]
+    fn decodes_linux_cooked_v2_tcp() {
+        let mut frame = vec![0x08, 0x00, 0, 0, 0, 0, 0, 3, 0, 1, 0, 6];
+        frame.extend_from_slice(&[0, 0x11, 0x22, 0x33, 0x44, 0x55, 0, 0]);
+        frame.extend_from_slice(&ipv4_packet(6, &tcp_segment(40000, 443, 0x02, &[])));
+        let data = build_pcap(276, &[&frame, &frame[..19]]);
+        let result = process_capture(&data, &ProcessOptions::default());
+        assert_eq!(result.packets[0].source, "10.0.0.1:40000");
+        assert_eq!(result.packets[0].destination, "10.0.0.2:443");
+        assert!(
+            result.packets[0]
+                .info
+                .summary
+                .contains("10.0.0.2:443 (https) [SYN]")
+        );
+        assert_eq!(result.packets[1].protocol, "LINKTYPE 276");
+    }
+
+    #[test]
+    fn classifies_from_first_packet() {
+        let packet = ipv4_packet(17, &udp_segment(5353, 53, &[]));
+        let classification = classify_capture(&build_pcap(101, &[&packet]));
+        assert_eq!(classification.format, "PCAP");
+        assert_eq!(classification.linktype, Some(101));
+        assert_eq!(classification.linktype_name.as_deref(), Some("RAW"));
+        assert_eq!(classification.first_protocol.as_deref(), Some("UDP"));
+
+        let mut pcapng = pcapng_shb(&[]);
+        pcapng.extend(pcapng_idb(1, &[]));
+        let classification = classify_capture(&pcapng);
+        assert_eq!(classification.format, "PCAPNG");
+        assert_eq!(classification.linktype, Some(1));
+        assert!(classification.first_protocol.is_none());
+
+        assert_eq!(classify_capture(&[]).format, "empty");
+        assert!(classify_capture(b"junk").linktype.is_none());
+    }
+
+    #[test]
+    fn dissects_single_ethernet_frame() {
+        let mut frame = vec![0xFF; 12];
+        frame.extend_from_slice(&[0x08, 0x00]);
+        frame.extend_from_slice(&ipv4_packet(17, &udp_segment(5353, 53, &[])));
+        let json: serde_json::Value = serde_json::from_str(&dissect_ethernet(&frame)).unwrap();
+        assert_eq!(json["packets"][0]["source"], "10.0.0.1:5353");
+        assert_eq!(json["packets"][0]["protocol_stack"], "eth:ip:udp");
+        assert_eq!(json["warnings"].as_array().map(Vec::len), Some(0));
+    }
+
+    #[test]
+    fn warns_when_frames_contradict_linktype() {
+        let packet = ipv4_packet(17, &udp_segment(5353, 53, &[]));
+        let data = build_pcap(1, &[&packet, &packet, &packet, &packet]);
+        let result = process_capture(&data, &ProcessOptions::default());
+        assert!(
+            result
+                .warnings
+                .contains(&"Header linktype 1 but frames look like raw IP (linktype 101)".into())
+        );
+        let data = build_pcap(101, &[&packet, &packet, &packet, &packet]);
+        let result = process_capture(&data, &ProcessOptions::default());
+        assert!(
+            !result
+                .warnings
+                .iter()
+                .any(|w| w.starts_with("Header linktype"))
+        );
+    }
+
+    #[test]
+    fn warns_about_implausible_timestamps() {
+        let packet = ipv4_packet(17, &udp_segment(5353, 53, &[]));
+        let mut data = build_pcap(101, &[&packet]);
+        let warning = "Capture contains timestamps before 2000 or after 2100, clock may be wrong";
+        let result = process_capture(&data, &ProcessOptions::default());
+        assert!(result.warnings.iter().any(|message| message == warning));
+        data[24..28].copy_from_slice(&1_700_000_000u32.to_le_bytes());
+        let result = process_capture(&data, &ProcessOptions::default());
+        assert!(!result.warnings.iter().any(|message| message == warning));
+    }
+
+    #[test]
+    fn ignores_untimed_simple_packets_in_timestamp_check() {
+        let micros = 1_700_000_000u64 * 1_000_000;
+        let mut epb = pcapng_epb(0, &[1], &[]);
+        epb[12..16].copy_from_slice(&((micros >> 32) as u32).to_le_bytes());
+        epb[16..20].copy_from_slice(&(micros as u32).to_le_bytes());
+        let mut data = pcapng_shb(&[]);
+        data.extend(pcapng_idb(147, &[]));
+        data.extend(epb);
+        data.extend(pcapng_spb(&[2]));
+        let result = process_capture(&data, &ProcessOptions::default());
+        assert_eq!(result.packets.len(), 2);
+        assert!(
+            !result
+                .warnings
+                .iter()
+                .any(|message| message.contains("clock"))
+        );
+    }
+
+    #[test]
+    fn decodes_linux_cooked_capture() {
+        let mut frame = vec![
+            0, 0, 0, 1, 0, 6, 0, 0x11, 0x22, 0x33, 0x44, 0x55, 0, 0, 0x08, 0x00,
+        ];
+        frame.extend_from_slice(&ipv4_packet(17, &udp_segment(5353, 53, &[])));
+        let data = build_pcap(113, &[&frame, &frame[..10]]);
+        let result = process_capture(&data, &ProcessOptions::default());
+        assert_eq!(result.packets[0].source, "10.0.0.1:5353");
+        assert_eq!(result.packets[0].protocol, "UDP");
+        assert_eq!(result.packets[1].protocol, "LINKTYPE 113");
+    }
+
+    #[test]
+    fn annotates_locations_with_cached_lookups() {
+        let packet = ipv4_packet(17, &udp_segment(5353, 53, &[]));
+        let data = build_pcap(101, &[&packet, &packet]);
+        let mut result = process_capture(&data, &ProcessOptions::default());
+        let mut lookups = 0;
+        annotate_locations(&mut result, |ip| {
+            lookups += 1;
+            (ip == "10.0.0.2").then(|| "US".to_string())
+        });
+        assert_eq!(lookups, 2);
+        let packet = &result.packets[1];
+        assert_eq!(packet.source, "10.0.0.1:5353");
+        assert_eq!(packet.destination, "10.0.0.2:53 (US)");
+    }
+
+    #[test]
+    fn caps_stored_payload_after_dissection() {
+        let packet = ipv4_packet(17, &udp_segment(5353, 53, &[0; 64]));
+        let data = build_pcap(101, &[&packet]);
+        let options = ProcessOptions {
+            max_payload_bytes: Some(16),
+            ..ProcessOptions::default()
+        };
+        let result = process_capture(&data, &options);
+        let packet = &result.packets[0];
+        assert_eq!(packet.payload.len(), 16);
+        assert_eq!(packet.length, 92);
+        assert_eq!(packet.destination, "10.0.0.2:53");
+        assert!(packet.info.summary.ends_with(" [payload truncated]"));
+
+        let mut frame = vec![0xFF; 12];
+        frame.extend_from_slice(&[0x08, 0x00]);
+        frame.extend_from_slice(&ipv4_packet(17, &udp_segment(5353, 53, &[])));
+        let data = build_pcap(1, &[&frame]);
+        let result = process_capture(&data, &options);
+        assert_eq!(result.packets[0].l3_offset, Some(14));
+        let options = ProcessOptions {
+            max_payload_bytes: Some(14),
+            ..ProcessOptions::default()
+        };
+        let result = process_capture(&data, &options);
+        assert_eq!(result.packets[0].l3_offset, None);
+    }
+
+    #[test]
+    fn flags_small_mss_on_syn() {
+        let mut syn = tcp_segment(40000, 443, 0x02, &[]);
+        syn[12] = 6 << 4;
+        syn.extend_from_slice(&[2, 4, 0x04, 0xAF]);
+        let analysis = parse_ipv4_packet(&ipv4_packet(6, &syn), Decap::default()).unwrap();
+        let tcp = analysis.layers.tcp.unwrap();
+        assert_eq!(tcp.mss, Some(1199));
+        assert_eq!(tcp.path_mtu, Some(1239));
+        assert!(analysis.summary.ends_with(" [small MSS 1199]"));
+        for mss in [1200u16, 1300] {
+            syn[22..24].copy_from_slice(&mss.to_be_bytes());
+            let analysis = parse_ipv4_packet(&ipv4_packet(6, &syn), Decap::default()).unwrap();
+            assert!(!analysis.summary.contains("small MSS"));
+        }
+    }
+
+    #[test]
+    fn notes_urgent_pointer_on_urg_segments() {
+        let mut segment = tcp_segment(40000, 23, 0x38, b"x");
+        segment[18..20].copy_from_slice(&1u16.to_be_bytes());
+        let first = ipv4_packet(6, &segment);
+        segment[18..20].copy_from_slice(&2u16.to_be_bytes());
+        let data = build_pcap(101, &[&first, &ipv4_packet(6, &segment)]);
+        let result = process_capture(&data, &ProcessOptions::default());
+        assert!(result.packets[0].info.summary.contains(" [urgent ptr=1]"));
+        assert!(result.packets[1].info.summary.contains(" [urgent ptr=2]"));
+        assert!(
+            result
+                .warnings
+                .contains(&"Note: TCP segment carries urgent data (x2)".to_string())
+        );
+    }
+
+    #[test]
+    fn reports_tcp_options_object() {
+        let mut syn = tcp_segment(40000, 443, 0x02, &[]);
+        syn[12] = 8 << 4;
+        syn.extend_from_slice(&[2, 4, 0x05, 0xB4, 4, 2, 1, 3, 3, 7, 0, 0]);
+        let data = build_pcap(101, &[&ipv4_packet(6, &syn)]);
+        let infos = packet_infos(&process_packet(&data));
+        let options = &infos[0]["tcp_options"];
+        assert_eq!(options["mss"], 1460);
+        assert_eq!(options["window_scale"], 7);
+        assert_eq!(options["sack_permitted"], true);
+        assert!(options.get("timestamps").is_none());
+    }
+
+    #[test]
+    fn exposes_tcp_sequence_numbers() {
+        let mut segment = tcp_segment(40000, 443, 0x10, &[]);
+        segment[4..8].copy_from_slice(&1000u32.to_be_bytes());
+        segment[8..12].copy_from_slice(&2000u32.to_be_bytes());
+        let udp = ipv4_packet(17, &udp_segment(5353, 53, &[]));
+        let short = ipv4_packet(6, &segment[..10]);
+        let data = build_pcap(101, &[&ipv4_packet(6, &segment), &udp, &short]);
+        let json: serde_json::Value = serde_json::from_str(&process_packet(&data)).unwrap();
+        let packets = json["packets"].as_array().unwrap();
+        assert_eq!(packets[0]["tcp_seq"], 1000);
+        assert_eq!(packets[0]["tcp_ack"], 2000);
+        assert!(packets[1]["tcp_seq"].is_null());
+        assert_eq!(packets[2]["tcp_seq"], 1000);
+        assert!(packets[2]["tcp_ack"].is_null());
+    }
+
+    #[test]
+    fn shows_tcp_timestamps_on_syn() {
+        let mut syn = tcp_segment(40000, 443, 0x02, &[]);
+        syn[12] = 8 << 4;
+        syn.extend_from_slice(&[1, 1, 8, 10, 0, 0, 0x30, 0x39, 0, 0, 0x30, 0x0C]);
+        let analysis = parse_ipv4_packet(&ipv4_packet(6, &syn), Decap::default()).unwrap();
+        assert_eq!(
+            analysis.layers.tcp.unwrap().timestamps,
+            Some((12345, 12300))
+        );
+        assert!(analysis.summary.ends_with(" [TSval=12345 TSecr=12300]"));
+
+        // A truncated option is ignored rather than misread.
+        syn.truncate(syn.len() - 2);
+        syn[12] = 7 << 4;
+        let analysis = parse_ipv4_packet(&ipv4_packet(6, &syn), Decap::default()).unwrap();
+        assert!(!analysis.summary.contains("TSval"));
+    }
+
+    #[test]
+    fn rebuilds_ipv6_neighbor_cache() {
+        let neighbor_message = |icmp_type: u8, source: u8, target: u8, option: u8, mac: u8| {
+            let mut packet = vec![0x60, 0, 0, 0, 0, 32, 58, 255];
+            packet.extend_from_slice(
+                &Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, source.into()).octets(),
+            );
+            packet.extend_from_slice(&Ipv6Addr::new(0xff02, 0, 0, 0, 0, 0, 0, 1).octets());
+            packet.extend_from_slice(&[icmp_type, 0, 0, 0, 0, 0, 0, 0]);
+            packet.extend_from_slice(
+                &Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, target.into()).octets(),
+            );
+            packet.extend_from_slice(&[option, 1, 0x02, 0, 0, 0, 0, mac]);
+            packet
+        };
+        let solicitation = neighbor_message(135, 9, 2, 1, 9);
+        let advertisement = neighbor_message(136, 2, 2, 2, 2);
+        let data = build_pcap(229, &[&advertisement, &solicitation]);
+        let result = process_capture(&data, &ProcessOptions::default());
+        assert_eq!(
+            result.neighbor_cache,
+            [
+                ("fe80::2".to_string(), "02:00:00:00:00:02".to_string()),
+                ("fe80::9".to_string(), "02:00:00:00:00:09".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn decapsulates_gre_over_ipv6() {
+        let mut inner = vec![0, 0, 0x08, 0x00];
+        inner.extend_from_slice(&ipv4_packet(17, &udp_segment(5353, 53, &[])));
+        let mut packet = vec![0x60, 0, 0, 0];
+        packet.extend_from_slice(&(inner.len() as u16).to_be_bytes());
+        packet.extend_from_slice(&[47, 64]);
+        packet.extend_from_slice(&Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1).octets());
+        packet.extend_from_slice(&Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 2).octets());
+        packet.extend_from_slice(&inner);
+        let analysis = parse_ipv6_packet(&packet, Decap::default()).unwrap();
+        assert_eq!(analysis.protocol, "UDP");
+        assert_eq!(analysis.destination, "10.0.0.2:53");
+        assert!(
+            analysis
+                .summary
+                .starts_with("GRE > UDP 10.0.0.1:5353 → 10.0.0.2:53")
+        );
+    }
+
+    #[test]
+    fn extracts_http_objects_across_segments() {
+        let request = b"GET /logo.png HTTP/1.1\r\nHost: example\r\n\r\n";
+        let head =
+            b"HTTP/1.1 200 OK\r\nContent-Type: image/png\r\nTransfer-Encoding: chunked\r\n\r\n";
+        let segment = |src: u16, dst: u16, seq: u32, data: &[u8]| {
+            let mut segment = tcp_segment(src, dst, 0x18, data);
+            segment[4..8].copy_from_slice(&seq.to_be_bytes());
+            let mut packet = ipv4_packet(6, &segment);
+            if src == 80 {
+                packet[12..20].copy_from_slice(&[10, 0, 0, 2, 10, 0, 0, 1]);
+            }
+            packet
+        };
+        let frames = [
+            segment(40000, 80, 1, request),
+            segment(80, 40000, 1 + head.len() as u32, b"3\r\nPNG\r\n0\r\n\r\n"),
+            segment(80, 40000, 1, head),
+        ];
+        let frames: Vec<&[u8]> = frames.iter().map(Vec::as_slice).collect();
+        let data = build_pcap(101, &frames);
+        let objects: serde_json::Value =
+            serde_json::from_str(&extract_http_objects(&data, true)).unwrap();
+        let object = &objects[0];
+        assert_eq!(object["method"], "GET");
+        assert_eq!(object["uri"], "/logo.png");
+        assert_eq!(object["status"], 200);
+        assert_eq!(object["content_type"], "image/png");
+        assert_eq!(object["body_length"], 3);
+        assert_eq!(object["body"], "UE5H");
+        assert_eq!(object["truncated"], false);
+        let objects: serde_json::Value =
+            serde_json::from_str(&extract_http_objects(&data, false)).unwrap();
+        assert!(objects[0].get("body").is_none());
+    }
+
+    #[test]
+    fn routes_igmp_to_its_dissector() {
+        let report = [0x22, 0, 0, 0, 0, 0, 0, 1, 4, 0, 0, 0, 239, 1, 1, 1];
+        let analysis = parse_ipv4_packet(&ipv4_packet(2, &report), Decap::default()).unwrap();
+        assert_eq!(analysis.protocol, "IGMP");
+        assert_eq!(analysis.summary, "IGMPv3 report: join 239.1.1.1");
+        // IGMPv2 queries keep the generic IP summary.
+        let query = [0x11, 100, 0, 0, 0, 0, 0, 0];
+        let analysis = parse_ipv4_packet(&ipv4_packet(2, &query), Decap::default()).unwrap();
+        assert_eq!(analysis.protocol, "IGMP");
+        assert_eq!(analysis.summary, "IGMP 10.0.0.1 → 10.0.0.2");
+    }
+
+    #[test]
+    fn routes_esp_after_ipv6_extension_headers() {
+        let ipv6 = |payload: &[u8]| {
+            // A hop-by-hop header padded with PadN precedes the ESP header.
+            let mut inner = vec![50, 0, 1, 4, 0, 0, 0, 0];
+            inner.extend_from_slice(payload);
+            let mut packet = vec![0x60, 0, 0, 0];
+            packet.extend_from_slice(&(inner.len() as u16).to_be_bytes());
+            packet.extend_from_slice(&[0, 64]);
+            packet.extend_from_slice(&Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1).octets());
+            packet.extend_from_slice(&Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 2).octets());
+            packet.extend_from_slice(&inner);
+            packet
+        };
+        let esp = [0, 0, 0xC0, 0xDE, 0, 0, 0, 7, 0xAA, 0xBB];
+        let analysis = parse_ipv6_packet(&ipv6(&esp), Decap::default()).unwrap();
+        assert_eq!(analysis.protocol, "ESP");
+        assert_eq!(analysis.summary, "ESP SPI=0x0000C0DE seq=7");
+        // Too short for the SPI and sequence number: generic summary.
+        let analysis = parse_ipv6_packet(&ipv6(&esp[..6]), Decap::default()).unwrap();
+        assert_eq!(analysis.protocol, "ESP");
+        assert_eq!(analysis.summary, "ESP 2001:db8::1 → 2001:db8::2");
+        let analysis = parse_ipv4_packet(&ipv4_packet(50, &esp), Decap::default()).unwrap();
+        assert_eq!(analysis.summary, "ESP SPI=0x0000C0DE seq=7");
+    }
+
+    #[test]
+    fn decodes_ptp_over_ethernet_and_udp() {
+        let mut ptp = vec![0u8; 44];
+        ptp[1] = 0x02;
+        let mut frame = vec![0x01, 0x1B, 0x19, 0, 0, 0, 0x02, 0, 0, 0, 0, 1, 0x88, 0xF7];
+        frame.extend_from_slice(&ptp);
+        ptp[0] = 0x08;
+        let udp = ipv4_packet(17, &udp_segment(320, 320, &ptp));
+        let data = build_pcap(1, &[&frame]);
+        let result = process_capture(&data, &ProcessOptions::default());
+        assert_eq!(result.packets[0].protocol, "PTP");
+        assert_eq!(result.packets[0].info.summary, "PTP Sync domain=0");
+        let result = process_capture(&build_pcap(101, &[&udp]), &ProcessOptions::default());
+        assert_eq!(result.packets[0].protocol, "PTP");
+        assert_eq!(result.packets[0].info.summary, "PTP Follow_Up domain=0");
+    }
+
+    #[test]
+    fn pairs_final_response_after_100_continue() {
+        let request =
+            b"POST /upload HTTP/1.1\r\nExpect: 100-continue\r\nContent-Length: 2\r\n\r\nhi";
+        let responses =
+            b"HTTP/1.1 100 Continue\r\n\r\nHTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok";
+        let client = ipv4_packet(6, &tcp_segment(40000, 80, 0x18, request));
+        let mut server = ipv4_packet(6, &tcp_segment(80, 40000, 0x18, responses));
+        server[12..20].copy_from_slice(&[10, 0, 0, 2, 10, 0, 0, 1]);
+        let data = build_pcap(101, &[&client, &server]);
+        let objects: serde_json::Value =
+            serde_json::from_str(&extract_http_objects(&data, false)).unwrap();
+        assert_eq!(objects.as_array().unwrap().len(), 1);
+        assert_eq!(objects[0]["method"], "POST");
+        assert_eq!(objects[0]["uri"], "/upload");
+        assert_eq!(objects[0]["status"], 200);
+        assert_eq!(objects[0]["body_length"], 2);
+    }
+
+    #[test]
+    fn warns_about_arp_with_ipv6_protocol_type() {
+        let mut frame = vec![0xFF; 6];
+        frame.extend_from_slice(&[0x02, 0, 0, 0, 0, 1, 0x08, 0x06]);
+        frame.extend_from_slice(&[0, 1, 0x86, 0xDD, 6, 16, 0, 1]);
+        frame.extend_from_slice(&[0; 44]);
+        let result = process_capture(&build_pcap(1, &[&frame]), &ProcessOptions::default());
+        assert_ne!(result.packets[0].protocol, "ARP");
+        assert!(result.warnings.contains(
+            &"ARP frame with IPv6 protocol type 0x86DD (IPv6 uses NDP, not ARP)".to_string()
+        ));
+    }
+
+    #[test]
+    fn decapsulates_gre_with_checksum_and_key() {
+        let mut gre = vec![0xA0, 0, 0x08, 0x00, 0, 0, 0, 0, 0, 0, 0, 5];
+        gre.extend_from_slice(&ipv4_packet(6, &tcp_segment(443, 51000, 0x10, &[])));
+        let analysis = parse_ipv4_packet(&ipv4_packet(47, &gre), Decap::default()).unwrap();
+        assert_eq!(analysis.protocol, "TCP");
+        assert_eq!(analysis.source, "10.0.0.1:443");
+        assert!(
+            analysis
+                .summary
+                .starts_with("GRE key 5 > TCP 10.0.0.1:443 → 10.0.0.2:51000")
+        );
+    }
+
+    #[test]
+    fn ascii_only_replaces_arrow_and_dash() {
+        let data = build_pcap(1, &[&[0xFF; 20]]);
+        let options = ProcessOptions {
+            ascii_only: true,
+            ..ProcessOptions::default()
+        };
+        let result = process_capture(&data, &options);
+        let packet = &result.packets[0];
+        assert!(packet.info.summary.contains(" -> "));
+        assert!(packet.info.summary.is_ascii());
+        let result = process_raw_payload(&[1, 2], &options);
+        assert_eq!(result.packets[0].destination, "-");
+    }
+
+    #[test]
+    fn surfaces_pptp_call_id() {
+        let mut gre = vec![0x30, 0x01, 0x88, 0x0B, 0, 0, 0x12, 0x34, 0, 0, 0, 1, 0x21];
+        gre.extend_from_slice(&ipv4_packet(17, &udp_segment(5353, 53, &[])));
+        let analysis = parse_ipv4_packet(&ipv4_packet(47, &gre), Decap::default()).unwrap();
+        assert_eq!(analysis.destination, "10.0.0.2:53");
+        assert!(
+            analysis
+                .summary
+                .starts_with("PPTP GRE call=0x1234 > UDP 10.0.0.1:5353 → 10.0.0.2:53")
+        );
+        let lcp = [
+            0x30, 0x01, 0x88, 0x0B, 0, 0, 0x12, 0x34, 0, 0, 0, 2, 0xFF, 0x03, 0xC0, 0x21,
+        ];
+        let analysis = parse_ipv4_packet(&ipv4_packet(47, &lcp), Decap::default()).unwrap();
+        assert_eq!(analysis.protocol, "PPTP");
+        assert_eq!(
+            analysis.summary,
+            "PPTP GRE call=0x1234 10.0.0.1 → 10.0.0.2 (PPP LCP)"
+        );
+    }
+
+    #[test]
+    fn hashes_input_when_requested() {
+        let options = ProcessOptions {
+            hash_file: true,
+            ..ProcessOptions::default()
+        };
+        let result = process_capture(b"abc", &options);
+        assert_eq!(
+            result.capture_info.file_sha256.as_deref(),
+            Some("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+        );
+        let result = process_capture(b"abc", &ProcessOptions::default());
+        assert!(result.capture_info.file_sha256.is_none());
+    }
+
+    #[test]
+    fn extracts_dns_messages_only() {
+        let mut query = vec![0xAB, 0xCD, 0x01, 0x00, 0, 1, 0, 0, 0, 0, 0, 0];
+        query.extend_from_slice(b"\x07example\x03com\x00\x00\x1c\x00\x01");
+        let dns = ipv4_packet(17, &udp_segment(5353, 53, &query));
+        let other = ipv4_packet(17, &udp_segment(5353, 54, &query));
+        let data = build_pcap(101, &[&other, &dns]);
+        let records: serde_json::Value = serde_json::from_str(&extract_dns(&data)).unwrap();
+        let records = records.as_array().unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0]["qname"], "example.com");
+        assert_eq!(records[0]["qtype"], "AAAA");
+        assert_eq!(records[0]["dst"], "10.0.0.2:53");
+        assert_eq!(records[0]["rcode"], 0);
+    }
+
+    #[test]
+    fn decapsulates_capwap_ethernet() {
+        let mut frame = vec![0xFF; 12];
+        frame.extend_from_slice(&[0x08, 0x00]);
+        frame.extend_from_slice(&ipv4_packet(17, &udp_segment(5353, 53, &[])));
+        let mut capwap = vec![0x00, 0x10, 0x02, 0x00, 0, 0, 0, 0];
+        capwap.extend_from_slice(&frame);
+        let packet = ipv4_packet(17, &udp_segment(5247, 5247, &capwap));
+        let analysis = parse_ipv4_packet(&packet, Decap::default()).unwrap();
+        assert_eq!(analysis.protocol, "CAPWAP");
+        assert!(
+            analysis
+                .summary
+                .starts_with("CAPWAP data \u{2192} UDP 10.0.0.1:5353")
+        );
+    }
+
+    #[test]
+    fn summarizes_dns_query() {
+        let mut query = vec![0xAB, 0xCD, 0x01, 0x00, 0, 1, 0, 0, 0, 0, 0, 0];
+        query.extend_from_slice(b"\x07example\x03com\x00\x00\x01\x00\x01");
+        let analysis = parse_ipv4_packet(
+            &ipv4_packet(17, &udp_segment(5353, 53, &query)),
+            Decap::default(),
+        )
+        .unwrap();
+        assert_eq!(analysis.protocol, "DNS");
+        assert_eq!(analysis.summary, "DNS A query example.com");
+        assert_eq!(analysis.source, "10.0.0.1:5353");
+    }
+
+    #[test]
+    fn filters_by_ip_version() {
+        let mut ipv4 = vec![0xFF; 12];
+        ipv4.extend_from_slice(&[0x08, 0x00]);
+        ipv4.extend_from_slice(&ipv4_packet(17, &udp_segment(5353, 53, &[])));
+        let mut ipv6 = vec![0xFF; 12];
+        ipv6.extend_from_slice(&[0x86, 0xDD, 0x60, 0, 0, 0, 0, 8, 17, 64]);
+        ipv6.extend_from_slice(&[0; 32]);
+        ipv6.extend_from_slice(&udp_segment(5353, 53, &[]));
+        let data = build_pcap(1, &[&ipv4, &ipv6, &ipv4]);
+        let options = ProcessOptions {
+            ip_version: Some(6),
+            ..ProcessOptions::default()
+        };
+        let result = process_capture(&data, &options);
+        assert_eq!(result.packets.len(), 1);
+        assert_eq!(result.packets[0].info.frame_number, 2);
+
+        // Dropped packets are never dissected, so they add no warnings.
+        let mut bad_checksum = ipv4.clone();
+        bad_checksum[24] ^= 0xFF;
+        let data = build_pcap(1, &[&bad_checksum, &ipv6]);
+        let options = ProcessOptions {
+            ip_version: Some(6),
+            verify_ipv4_checksum: true,
+            ..ProcessOptions::default()
+        };
+        let result = process_capture(&data, &options);
+        assert_eq!(result.packets.len(), 1);
+        assert!(
+            result
+                .warnings
+                .iter()
+                .all(|warning| !warning.contains("checksum"))
+        );
+        let result = process_capture(
+            &data,
+            &ProcessOptions {
+                ip_version: None,
+                ..options
+            },
+        );
+        assert!(
+            result
+                .warnings
+                .iter()
+                .any(|warning| warning.contains("checksum"))
+        );
+    }
+
+    #[cfg(feature = "msgpack")]
+    #[test]
+    fn msgpack_output_matches_json_schema() {
+        let data = build_pcap(101, &[&ipv4_packet(17, &udp_segment(1, 2, &[]))]);
+        let decoded: serde_json::Value =
+            rmp_serde::from_slice(&process_packet_msgpack(&data)).unwrap();
+        let json: serde_json::Value = serde_json::from_str(&process_packet(&data)).unwrap();
+        assert_eq!(decoded, json);
+    }
+
+    #[test]
+    fn keeps_partial_final_record() {
+        let packet = ipv4_packet(17, &udp_segment(5353, 53, &[0; 8]));
+        let mut data = build_pcap(101, &[&packet, &packet]);
+        data.truncate(data.len() - 4);
+        let result = process_capture(&data, &ProcessOptions::default());
+        assert_eq!(result.packets.len(), 2);
+        let last = &result.packets[1];
+        assert_eq!(last.payload, packet[..packet.len() - 4]);
+        assert!(last.info.summary.ends_with(" [truncated file]"));
+        assert!(result.warnings.contains(
+            &"Packet 2 cut off by end of file (32 of 36 captured bytes present)".to_string()
+        ));
+    }
+
+    #[test]
+    fn stops_at_max_decapsulation_depth() {
+        let mut packet = ipv4_packet(17, &udp_segment(5353, 53, &[]));
+        for _ in 0..12 {
+            packet = ipv4_packet(4, &packet);
+        }
+        let data = build_pcap(101, &[&packet]);
+        let result = process_capture(&data, &ProcessOptions::default());
+        let summary = &result.packets[0].info.summary;
+        assert!(summary.ends_with("> IP 10.0.0.1 → 10.0.0.2 [max decapsulation depth reached]"));
+        assert_eq!(summary.matches("IP-in-IP > ").count(), 8);
+
+        let options = ProcessOptions {
+            max_decap_depth: 12,
+            ..ProcessOptions::default()
+        };
+        let result = process_capture(&data, &options);
+        let summary = &result.packets[0].info.summary;
+        assert!(summary.contains("IP-in-IP > UDP 10.0.0.1:5353 → 10.0.0.2:53"));
+        assert!(!summary.contains("max decapsulation depth"));
+    }
+
+    #[test]
+    fn labels_packets_with_interface_names() {
+        let packet = ipv4_packet(17, &udp_segment(5353, 53, &[]));
+        let mut data = pcapng_shb(&[]);
+        data.extend_from_slice(&pcapng_idb(101, &[(2, b"eth0"), (3, b"uplink")]));
+        data.extend_from_slice(&pcapng_idb(101, &[]));
+        data.extend_from_slice(&pcapng_epb(1, &packet, &[]));
+        data.extend_from_slice(&pcapng_epb(0, &packet, &[]));
+        let result = process_capture(&data, &ProcessOptions::default());
+        assert_eq!(result.packets[0].info.interface.as_deref(), Some("if1"));
+        assert_eq!(result.packets[0].info.interface_description, None);
+        assert_eq!(result.packets[1].info.interface.as_deref(), Some("eth0"));
+        assert_eq!(
+            result.packets[1].info.interface_description.as_deref(),
+            Some("uplink")
+        );
+
+        let data = build_pcap(101, &[&packet]);
+        let result = process_capture(&data, &ProcessOptions::default());
+        assert_eq!(result.packets[0].info.interface, None);
+    }
+
+    #[test]
+    fn resolves_names_from_name_resolution_block() {
+        let mut record = vec![10, 0, 0, 2];
+        record.extend_from_slice(b"server.local\0");
+        let mut nrb = 1u16.to_le_bytes().to_vec();
+        nrb.extend_from_slice(&(record.len() as u16).to_le_bytes());
+        nrb.extend_from_slice(&record);
+        nrb.resize(nrb.len().next_multiple_of(4), 0);
+        nrb.extend_from_slice(&[0; 4]);
+
+        let mut data = pcapng_shb(&[]);
+        data.extend_from_slice(&pcapng_idb(101, &[]));
+        data.extend_from_slice(&pcapng_epb(
+            0,
+            &ipv4_packet(17, &udp_segment(5353, 53, &[])),
+            &[],
+        ));
+        data.extend_from_slice(&pcapng_block(4, &nrb));
+        let result = process_capture(&data, &ProcessOptions::default());
+        assert_eq!(result.packets[0].source, "10.0.0.1:5353");
+        assert_eq!(result.packets[0].destination, "server.local (10.0.0.2:53)");
+    }
+
+    #[test]
+    fn warns_about_interface_drop_counts() {
+        let mut isb = 0u32.to_le_bytes().to_vec();
+        isb.extend_from_slice(&[0; 8]);
+        isb.extend_from_slice(&pcapng_options(&[
+            (4, &10_000u64.to_le_bytes()),
+            (5, &512u64.to_le_bytes()),
+        ]));
+        let earlier = {
+            let mut isb = 0u32.to_le_bytes().to_vec();
+            isb.extend_from_slice(&[0; 8]);
+            isb.extend_from_slice(&pcapng_options(&[(5, &7u64.to_le_bytes())]));
+            isb
+        };
+        let mut data = pcapng_shb(&[]);
+        data.extend_from_slice(&pcapng_idb(101, &[(2, b"eth0")]));
+        data.extend_from_slice(&pcapng_block(5, &earlier));
+        data.extend_from_slice(&pcapng_block(5, &isb));
+        let mut unknown = isb.clone();
+        unknown[..4].copy_from_slice(&3u32.to_le_bytes());
+        data.extend_from_slice(&pcapng_block(5, &unknown));
+        let result = process_capture(&data, &ProcessOptions::default());
+        let drops: Vec<&String> = result
+            .warnings
+            .iter()
+            .filter(|warning| warning.contains("dropped"))
+            .collect();
+        assert_eq!(
+            drops,
+            ["Interface eth0 reported 512 dropped packets of 10000 received"]
+        );
+        assert!(
+            result
+                .warnings
+                .contains(&"Interface statistics reference unknown interface 3".to_string())
+        );
+    }
+
+    #[test]
+    fn simple_packets_use_interface_zero_linktype() {
+        let packet = ipv4_packet(17, &udp_segment(5353, 53, &[]));
+        let mut data = pcapng_shb(&[]);
+        data.extend_from_slice(&pcapng_spb(&packet));
+        data.extend_from_slice(&pcapng_idb(101, &[]));
+        data.extend_from_slice(&pcapng_spb(&packet));
+        let result = process_capture(&data, &ProcessOptions::default());
+        assert!(
+            result
+                .warnings
+                .contains(&"Simple packet appears before any interface description".to_string())
+        );
+        assert_eq!(result.packets.len(), 1);
+        assert_eq!(result.packets[0].info.frame_number, 2);
+        assert_eq!(result.packets[0].protocol, "UDP");
+        assert_eq!(result.packets[0].time, "0.000000");
+    }
+
+    #[test]
+    fn decompresses_gzipped_captures() {
+        use flate2::Compression;
+        use flate2::write::GzEncoder;
+        use std::io::Write;
+
+        let data = build_pcap(101, &[&ipv4_packet(17, &udp_segment(5353, 53, &[]))]);
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&data).unwrap();
+        let compressed = encoder.finish().unwrap();
+        let result = process_capture(&compressed, &ProcessOptions::default());
+        assert!(result.errors.is_empty());
+        assert_eq!(result.packets[0].protocol, "UDP");
+
+        let result = process_capture(&compressed[..20], &ProcessOptions::default());
+        assert_eq!(result.packets[0].protocol, "RAW");
+        assert!(result.errors[0].starts_with("Gzip decompression failed"));
+    }
+
+    #[test]
+    fn reassembles_fragmented_udp_datagram() {
+        let datagram = ipv4_packet(17, &udp_segment(5000, 6000, &[7; 16]));
+        let mut first = datagram[..20].to_vec();
+        first.extend_from_slice(&datagram[20..36]);
+        first[2..4].copy_from_slice(&36u16.to_be_bytes());
+        first[6..8].copy_from_slice(&0x2000u16.to_be_bytes());
+        let mut second = datagram[..20].to_vec();
+        second.extend_from_slice(&datagram[36..]);
+        second[2..4].copy_from_slice(&28u16.to_be_bytes());
+        second[6..8].copy_from_slice(&2u16.to_be_bytes());
+
+        let result = process_capture(
+            &build_pcap(101, &[&first, &second]),
+            &ProcessOptions::default(),
+        );
+        assert_eq!(result.packets.len(), 2);
+        assert!(
+            result.packets[0]
+                .info
+                .summary
+                .starts_with("UDP 10.0.0.1:5000")
+        );
+        assert_eq!(
+            result.packets[1].info.summary,
+            "UDP 10.0.0.1:5000 → 10.0.0.2:6000 [reassembled from 2 fragments]"
+        );
+        assert!(
+            !result
+                .warnings
+                .iter()
+                .any(|warning| warning.contains("missing fragments"))
+        );
+
+        let result = process_capture(&build_pcap(101, &[&second]), &ProcessOptions::default());
+        assert_eq!(
+            result.packets[0].info.summary,
+            "UDP 10.0.0.1 → 10.0.0.2 [fragment]"
+        );
+        assert!(
+            result
+                .warnings
+                .contains(&"1 IPv4 datagram(s) missing fragments at end of capture".to_string())
+        );
+    }
+
+    #[test]
+    fn flags_bad_ipv4_checksum_when_enabled() {
+        let mut good = ipv4_packet(17, &udp_segment(5353, 53, &[]));
+        let checksum = internet_checksum(&good[..20]);
+        good[10..12].copy_from_slice(&checksum.to_be_bytes());
+        let mut bad = good.clone();
+        bad[8] = 1;
+        let data = build_pcap(101, &[&good, &bad]);
+        let options = ProcessOptions {
+            verify_ipv4_checksum: true,
+            ..ProcessOptions::default()
+        };
+        let result = process_capture(&data, &options);
+        assert!(!result.packets[0].info.summary.contains("[bad checksum]"));
+        assert!(result.packets[1].info.summary.ends_with(" [bad checksum]"));
+        assert!(
+            result
+                .warnings
+                .contains(&"IPv4 header checksum mismatch".to_string())
+        );
+
+        let result = process_capture(&data, &ProcessOptions::default());
+        assert!(!result.packets[1].info.summary.contains("[bad checksum]"));
+    }
+
+    #[test]
+    fn keeps_first_packet_per_flow() {
+        let request = ipv4_packet(17, &udp_segment(5353, 53, &[]));
+        let mut reply = ipv4_packet(17, &udp_segment(53, 5353, &[]));
+        reply[12..16].copy_from_slice(&[10, 0, 0, 2]);
+        reply[16..20].copy_from_slice(&[10, 0, 0, 1]);
+        let other = ipv4_packet(17, &udp_segment(5354, 53, &[]));
+        let data = build_pcap(101, &[&request, &reply, &other, &request]);
+        let options = ProcessOptions {
+            first_per_flow: true,
+            ..ProcessOptions::default()
+        };
+        let result = process_capture(&data, &options);
+        assert_eq!(result.packets.len(), 2);
+        assert!(
+            result.packets[0]
+                .info
+                .summary
+                .ends_with(" [3 packets in flow]")
+        );
+        assert_eq!(result.packets[1].source, "10.0.0.1:5354");
+    }
+
+    #[test]
+    fn reports_nanosecond_epoch_when_requested() {
+        let data = build_pcap(147, &[&[1], &[2]]);
+        let options = ProcessOptions {
+            ns_timestamps: true,
+            ..ProcessOptions::default()
+        };
+        let result = process_capture(&data, &options);
+        assert_eq!(result.packets[1].info.epoch_ns, Some(1_000_000_000));
+        let json = serde_json::to_value(&result.packets[1]).unwrap();
+        assert_eq!(json["epoch_ns"], "1000000000");
+        assert_eq!(epoch_nanos(1, 5, 1_000_000_000), 1_000_000_005);
+        assert_eq!(epoch_nanos(i64::MAX / 2, 999_999, 1_000_000), i64::MAX);
+        let result = process_capture(&data, &ProcessOptions::default());
+        assert!(result.packets[1].info.epoch_ns.is_none());
+    }
+
+    #[test]
+    fn dissects_length_prefixed_records() {
+        let packet = ipv4_packet(17, &udp_segment(5353, 53, &[]));
+        let mut data = (packet.len() as u32).to_be_bytes().to_vec();
+        data.extend_from_slice(&packet);
+        data.extend_from_slice(&[0, 0, 0, 9, 1]);
+        let result = walk_raw_framed(&data, Some(101), &ProcessOptions::default());
+        assert_eq!(result.packets.len(), 1);
+        assert_eq!(result.packets[0].destination, "10.0.0.2:53");
+        assert_eq!(result.warnings, ["Record 2 length exceeds remaining data"]);
+        let result = walk_raw_framed(&data[..packet.len() + 4], None, &ProcessOptions::default());
+        assert_eq!(result.packets[0].protocol, "EtherType 0x0A00");
+        assert!(result.warnings[0].contains("assuming Ethernet"));
+    }
+
+    #[test]
+    fn compact_output_emits_summary_once() {
+        let data = build_pcap(147, &[&[1, 2, 3]]);
+        let result: serde_json::Value =
+            serde_json::from_str(&process_packet_compact(&data)).unwrap();
+        let packet = &result["packets"][0];
+        assert_eq!(packet["summary"], "Captured 3 bytes (linktype 147)");
+        assert_eq!(packet["frame_number"], 1);
+        assert_eq!(packet["hex_preview"], "01 02 03");
+        assert!(packet.get("info").is_none());
+    }
+
+    #[test]
+    fn flags_router_alert_on_igmp() {
+        let mut packet = vec![0u8; 32];
+        packet[0] = 0x46;
+        packet[3] = 32;
+        packet[9] = 2;
+        packet[12..16].copy_from_slice(&[10, 0, 0, 1]);
+        packet[16..20].copy_from_slice(&[224, 0, 0, 22]);
+        packet[20..24].copy_from_slice(&[148, 4, 0, 0]);
+        let analysis = parse_ipv4_packet(&packet, Decap::default()).unwrap();
+        assert!(analysis.summary.ends_with(" [Router Alert: IGMP]"));
+    }
+
+    #[test]
+    fn dissects_vlan_tagged_arp() {
+        let mut frame = vec![0xFF; 6];
+        frame.extend_from_slice(&[0x02, 0, 0, 0, 0, 1]);
+        frame.extend_from_slice(&[0x81, 0x00, 0xA0, 0x64, 0x08, 0x06]);
+        frame.extend_from_slice(&[0, 1, 0x08, 0x00, 6, 4, 0, 1]);
+        frame.extend_from_slice(&[0x02, 0, 0, 0, 0, 1, 10, 0, 0, 1]);
+        frame.extend_from_slice(&[0, 0, 0, 0, 0, 0, 10, 0, 0, 2]);
+        let analysis = analyze_ethernet_frame(&frame, &ProcessOptions::default(), Decap::default());
+        assert_eq!(analysis.protocol, "ARP");
+        assert!(
+            analysis
+                .summary
+                .starts_with("VLAN 100 ARP who-has 10.0.0.2 tell 10.0.0.1 (02:00:00:00:00:01")
+        );
+        let ethernet = analysis.layers.ethernet.unwrap();
+        assert_eq!(ethernet.ethertype, 0x8100);
+        assert_eq!((ethernet.vlan[0].id, ethernet.vlan[0].pcp), (100, 5));
+
+        let tag_only =
+            analyze_ethernet_frame(&frame[..18], &ProcessOptions::default(), Decap::default());
+        assert_eq!(tag_only.protocol, "EtherType 0x0806");
+        assert!(tag_only.summary.starts_with("VLAN 100 Ethernet 0x0806"));
+    }
+
+    #[test]
+    fn labels_runt_ethernet_frames() {
+        let analysis =
+            analyze_ethernet_frame(&[0xFF; 6], &ProcessOptions::default(), Decap::default());
+        assert_eq!(analysis.protocol, "Error");
+        assert_eq!(
+            analysis.summary,
+            "Runt frame (6 bytes, too short for Ethernet)"
+        );
+    }
+
+    #[test]
+    fn payload_keeps_link_header_and_marks_l3_offset() {
+        let ip = ipv4_packet(17, &udp_segment(5353, 53, &[]));
+        let mut frame = vec![0xFF; 12];
+        frame.extend_from_slice(&[0x81, 0x00, 0x00, 0x0A, 0x08, 0x00]);
+        frame.extend_from_slice(&ip);
+        let result = process_capture(&build_pcap(1, &[&frame]), &ProcessOptions::default());
+        assert_eq!(result.packets[0].payload, frame);
+        assert_eq!(result.packets[0].l3_offset, Some(18));
+
+        let result = process_capture(&build_pcap(101, &[&ip]), &ProcessOptions::default());
+        assert_eq!(result.packets[0].l3_offset, Some(0));
+        let result = process_capture(&build_pcap(147, &[&ip]), &ProcessOptions::default());
+        assert_eq!(result.packets[0].l3_offset, None);
+    }
+
+    #[test]
+    fn summarizes_pause_frames() {
+        let mut frame = vec![
+            0x01, 0x80, 0xC2, 0, 0, 0x01, 0, 0x11, 0x22, 0x33, 0x44, 0x55,
+        ];
+        frame.extend_from_slice(&[0x88, 0x08, 0x00, 0x01, 0xFF, 0xFF]);
+        frame.resize(60, 0);
+        let analysis = analyze_ethernet_frame(&frame, &ProcessOptions::default(), Decap::default());
+        assert_eq!(analysis.protocol, "Control");
+        assert_eq!(analysis.summary, "Ethernet PAUSE time=65535");
+        assert_eq!(analysis.source, "00:11:22:33:44:55");
+
+        // Opcode present but the quanta cut off.
+        let analysis =
+            analyze_ethernet_frame(&frame[..16], &ProcessOptions::default(), Decap::default());
+        assert_eq!(analysis.summary, "Ethernet MAC Control opcode 0x0001");
+    }
+
+    #[test]
+    fn unwraps_qinq_tag_stack() {
+        let mut frame = vec![0xFF; 12];
+        frame.extend_from_slice(&[0x88, 0xA8, 0x00, 0x0A, 0x81, 0x00, 0x00, 0xC8, 0x08, 0x00]);
+        frame.extend_from_slice(&ipv4_packet(17, &udp_segment(5353, 53, &[])));
+        let analysis = analyze_ethernet_frame(&frame, &ProcessOptions::default(), Decap::default());
+        assert!(
+            analysis
+                .summary
+                .starts_with("VLAN 10>200 UDP 10.0.0.1:5353")
+        );
+        assert_eq!(analysis.layers.ethernet.unwrap().vlan.len(), 2);
+
+        let mut looping = vec![0xFF; 12];
+        for _ in 0..6 {
+            looping.extend_from_slice(&[0x81, 0x00, 0x00, 0x01]);
+        }
+        let analysis =
+            analyze_ethernet_frame(&looping, &ProcessOptions::default(), Decap::default());
+        assert!(analysis.summary.starts_with("VLAN 1>1>1>1 Ethernet 0x8100"));
+    }
+
+    #[test]
+    fn decodes_mpls_label_stack() {
+        let mut frame = vec![0xFF; 12];
+        frame.extend_from_slice(&[0x88, 0x47, 0x03, 0xE8, 0x01, 0x40]);
+        frame.extend_from_slice(&ipv4_packet(17, &udp_segment(5353, 53, &[])));
+        let analysis = analyze_ethernet_frame(&frame, &ProcessOptions::default(), Decap::default());
+        assert!(analysis.summary.starts_with("MPLS 16000 UDP 10.0.0.1:5353"));
+        assert_eq!(analysis.layers.mpls[0].label, 16000);
+
+        frame[16] = 0x00;
+        let analysis =
+            analyze_ethernet_frame(&frame[..18], &ProcessOptions::default(), Decap::default());
+        assert_eq!(analysis.summary, "MPLS 16000 (no bottom of stack)");
+        assert_eq!(analysis.protocol, "MPLS");
+    }
+
+    #[test]
+    fn labels_mld_behind_router_alert() {
+        let mut packet = vec![0u8; 40];
+        packet[0] = 0x60;
+        packet[6] = 0;
+        packet[7] = 1;
+        packet[8] = 0xFE;
+        packet[9] = 0x80;
+        packet[23] = 1;
+        packet[24] = 0xFF;
+        packet[25] = 0x02;
+        packet[39] = 0x16;
+        packet.extend_from_slice(&[58, 0, 5, 2, 0, 0, 1, 0]);
+        packet.extend_from_slice(&[143, 0, 0, 0, 0, 0, 0, 0]);
+        let analysis = parse_ipv6_packet(&packet, Decap::default()).unwrap();
+        assert_eq!(analysis.protocol, "MLDv2");
+        assert_eq!(
+            analysis.summary,
+            "MLDv2 fe80::1 → ff02::16 (multicast listener report v2) [Router Alert: MLD]"
+        );
+    }
+
+    #[test]
+    fn flags_jumbo_frames_past_threshold() {
+        let mut frame = vec![0u8; 12];
+        frame.extend_from_slice(&[0x08, 0x00]);
+        frame.extend_from_slice(&ipv4_packet(17, &udp_segment(1, 2, &[0; 2000])));
+        let analysis = analyze_ethernet_frame(&frame, &ProcessOptions::default(), Decap::default());
+        assert!(analysis.summary.ends_with(" [jumbo]"));
+        let options = ProcessOptions {
+            jumbo_frame_threshold: 9018,
+            ..ProcessOptions::default()
+        };
+        let analysis = analyze_ethernet_frame(&frame, &options, Decap::default());
+        assert!(!analysis.summary.contains("[jumbo]"));
+        let analysis =
+            analyze_ethernet_frame(&frame[..1518], &ProcessOptions::default(), Decap::default());
+        assert!(!analysis.summary.contains("[jumbo]"));
+    }
+
+    #[test]
+    fn flags_late_hop_by_hop_header() {
+        let mut packet = vec![0x60, 0, 0, 0, 0, 16, 60, 64];
+        packet.extend_from_slice(&[0; 32]);
+        packet.extend_from_slice(&[0, 0, 1, 4, 0, 0, 0, 0]);
+        packet.extend_from_slice(&[59, 0, 1, 4, 0, 0, 0, 0]);
+        let analysis = parse_ipv6_packet(&packet, Decap::default()).unwrap();
+        assert!(analysis.summary.ends_with(" [ext header order violation]"));
+    }
+
+    #[test]
+    fn dissects_sip_on_udp_and_tcp() {
+        let request = b"REGISTER sip:example.com SIP/2.0\r\n";
+        let udp = ipv4_packet(17, &udp_segment(5060, 5060, request));
+        let analysis = parse_ipv4_packet(&udp, Decap::default()).unwrap();
+        assert_eq!(analysis.protocol, "SIP");
+        assert_eq!(analysis.summary, "REGISTER sip:example.com SIP/2.0");
+
+        let tcp = ipv4_packet(
+            6,
+            &tcp_segment(40000, 5060, 0x18, b"SIP/2.0 180 Ringing\r\n"),
+        );
+        assert_eq!(
+            parse_ipv4_packet(&tcp, Decap::default()).unwrap().summary,
+            "SIP/2.0 180 Ringing"
+        );
+    }
+
+    #[test]
+    fn surfaces_ipv6_tunnel_encapsulation_limit() {
+        let mut packet = vec![0u8; 40];
+        packet[0] = 0x60;
+        packet[6] = 60;
+        packet[7] = 64;
+        packet[8] = 0x20;
+        packet[24] = 0x20;
+        packet.extend_from_slice(&[59, 0, 4, 1, 2, 1, 1, 0]);
+        let analysis = parse_ipv6_packet(&packet, Decap::default()).unwrap();
+        assert!(analysis.summary.ends_with(" [tunnel encap limit 2]"));
+    }
+
+    #[test]
+    fn flags_truncated_option_in_hop_by_hop_header() {
+        let mut packet = vec![0u8; 40];
+        packet[0] = 0x60;
+        packet[7] = 64;
+        // PadN claiming five bytes where only four remain in the header.
+        packet.extend_from_slice(&[59, 0, 1, 5, 0, 0, 0, 0]);
+        let analysis = parse_ipv6_packet(&packet, Decap::default()).unwrap();
+        assert!(
+            analysis
+                .summary
+                .ends_with(" [malformed ext header options]")
+        );
+
+        packet[43] = 4;
+        let analysis = parse_ipv6_packet(&packet, Decap::default()).unwrap();
+        assert!(!analysis.summary.contains("malformed"));
+    }
+
+    #[test]
+    fn collapses_repeated_warnings() {
+        let mut warnings = Vec::new();
+        for _ in 0..3 {
+            push_warning(&mut warnings, "bad block".to_string());
+        }
+        push_warning(&mut warnings, "other".to_string());
+        push_warning(&mut warnings, "bad block".to_string());
+        assert_eq!(warnings, vec!["bad block (x3)", "other", "bad block"]);
+    }
+
+    #[test]
+    fn collapses_truncation_warnings_across_packets() {
+        let frame = [0u8; 14];
+        let mut data = build_pcap(1, &[&frame, &frame]);
+        data[36..40].copy_from_slice(&100u32.to_le_bytes());
+        data[66..70].copy_from_slice(&100u32.to_le_bytes());
+        let result = process_capture(&data, &ProcessOptions::default());
+        assert_eq!(
+            result.warnings[0],
+            "Packet truncated to 14 of 100 bytes (x2)"
+        );
+    }
+
+    #[test]
+    fn unknown_linktype_keeps_raw_ip_analysis() {
+        let mut packet = vec![0u8; 20];
+        packet[0] = 0x45;
+        packet[3] = 20;
+        packet[9] = 6;
+        packet[12..16].copy_from_slice(&[10, 0, 0, 1]);
+        packet[16..20].copy_from_slice(&[10, 0, 0, 2]);
+        let mut warnings = Vec::new();
+        let analysis = analyze_payload(4242, &packet, &ProcessOptions::default(), &mut warnings);
+        assert_eq!(analysis.source, "10.0.0.1");
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn ip_guess_can_be_disabled() {
+        let packet = ipv4_packet(6, &tcp_segment(1, 2, 0x02, &[]));
+        let options = ProcessOptions {
+            disable_ip_guess: true,
+            ..ProcessOptions::default()
+        };
+        let mut warnings = Vec::new();
+        let analysis = analyze_payload(4242, &packet, &options, &mut warnings);
+        assert_eq!(analysis.protocol, "LINKTYPE 4242");
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn unknown_linktype_warns_once_when_raw_ip_fails() {
+        let mut warnings = Vec::new();
+        let analysis = analyze_payload(4242, &[0xFF; 8], &ProcessOptions::default(), &mut warnings);
+        analyze_payload(4242, &[0xFF; 8], &ProcessOptions::default(), &mut warnings);
+        assert_eq!(analysis.protocol, "LINKTYPE 4242");
+        assert_eq!(warnings, vec!["Unknown linktype 4242, treated as raw IP"]);
     }
 
     #[test]
@@ -945,6 +4089,7 @@ mod tests {
                 source_mac: "00:11:22:33:44:55".to_string(),
                 destination_mac: "66:77:88:99:aa:bb".to_string(),
                 ethertype: 0x86DD,
+                vlan: Vec::new(),
             }),
             ..Default::default()
         };
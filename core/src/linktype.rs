@@ -0,0 +1,76 @@
+use crate::{Decap, analyze_raw_ip, push_warning};
+
+/// Records examined before the verdict is fixed, keeping the check cheap on
+/// large captures.
+const SAMPLE_SIZE: usize = 64;
+/// Sampled records needed before a mismatch is reported at all.
+const MIN_SAMPLES: usize = 4;
+
+/// Samples the first records of a pcap file to spot a header linktype that
+/// disagrees with the frames: Ethernet headers around raw IP packets, or the
+/// reverse.
+pub struct LinktypeCheck {
+    linktype: u32,
+    sampled: usize,
+    mismatched: usize,
+}
+
+impl LinktypeCheck {
+    pub fn new(linktype: u32) -> LinktypeCheck {
+        LinktypeCheck {
+            linktype,
+            sampled: 0,
+            mismatched: 0,
+        }
+    }
+
+    pub fn observe(&mut self, frame: &[u8]) {
+        if self.sampled >= SAMPLE_SIZE {
+            return;
+        }
+        let mismatch = match self.linktype {
+            1 => !looks_like_ethernet(frame) && analyze_raw_ip(frame, Decap::default()).is_some(),
+            101 | 228 | 229 => {
+                analyze_raw_ip(frame, Decap::default()).is_none() && looks_like_ethernet(frame)
+            }
+            _ => return,
+        };
+        self.sampled += 1;
+        if mismatch {
+            self.mismatched += 1;
+        }
+    }
+
+    /// Warns when at least three quarters of the sampled records match the
+    /// other framing.
+    pub fn finish(&self, warnings: &mut Vec<String>) {
+        if self.sampled < MIN_SAMPLES || self.mismatched * 4 < self.sampled * 3 {
+            return;
+        }
+        let other = if self.linktype == 1 {
+            "raw IP (linktype 101)"
+        } else {
+            "Ethernet (linktype 1)"
+        };
+        push_warning(
+            warnings,
+            format!(
+                "Header linktype {} but frames look like {other}",
+                self.linktype
+            ),
+        );
+    }
+}
+
+/// An Ethernet header whose EtherType is IP, ARP or a VLAN tag, with a
+/// parseable IP packet behind it when the EtherType says IP.
+fn looks_like_ethernet(frame: &[u8]) -> bool {
+    let Some(ethertype) = frame.get(12..14) else {
+        return false;
+    };
+    match u16::from_be_bytes([ethertype[0], ethertype[1]]) {
+        0x0800 | 0x86DD => analyze_raw_ip(&frame[14..], Decap::default()).is_some(),
+        0x0806 | 0x8100 | 0x88A8 => true,
+        _ => false,
+    }
+}
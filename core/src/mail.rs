@@ -0,0 +1,75 @@
+use crate::text::{first_line, truncate_line};
+
+/// Summarizes a POP3 `+OK`/`-ERR` status line as (protocol, summary), putting
+/// `-ERR` in the `Error` protocol category so failed logins stand out.
+pub fn parse_pop3_response(payload: &[u8]) -> Option<(&'static str, String)> {
+    let line = first_line(payload)?;
+    let failed = if line.starts_with("+OK") {
+        false
+    } else if line.starts_with("-ERR") {
+        true
+    } else {
+        return None;
+    };
+    Some(label("POP3", failed, line))
+}
+
+/// Summarizes an IMAP tagged or untagged `OK`/`NO`/`BAD` response like
+/// [`parse_pop3_response`], with `NO` and `BAD` in the `Error` category.
+pub fn parse_imap_response(payload: &[u8]) -> Option<(&'static str, String)> {
+    let line = first_line(payload)?;
+    let mut words = line.splitn(3, ' ');
+    let tag = words.next()?;
+    let status = words.next()?;
+    let tag_valid = tag == "*"
+        || !tag.is_empty()
+            && tag
+                .bytes()
+                .all(|byte| byte.is_ascii_alphanumeric() || byte == b'.');
+    if !tag_valid {
+        return None;
+    }
+    let failed = match status.to_ascii_uppercase().as_str() {
+        "OK" | "PREAUTH" | "BYE" => false,
+        "NO" | "BAD" => true,
+        _ => return None,
+    };
+    Some(label("IMAP", failed, line))
+}
+
+fn label(protocol: &'static str, failed: bool, line: &str) -> (&'static str, String) {
+    let category = if failed { "Error" } else { protocol };
+    (category, format!("{protocol} {}", truncate_line(line)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn flags_failed_pop3_login() {
+        assert_eq!(
+            parse_pop3_response(b"-ERR [AUTH] invalid password\r\n"),
+            Some(("Error", "POP3 -ERR [AUTH] invalid password".to_string()))
+        );
+        assert_eq!(
+            parse_pop3_response(b"+OK ready\r\n"),
+            Some(("POP3", "POP3 +OK ready".to_string()))
+        );
+        assert!(parse_pop3_response(b"USER bob\r\n").is_none());
+    }
+    #[test]
+    fn flags_imap_no_and_bad() {
+        assert_eq!(
+            parse_imap_response(b"a001 NO [AUTHENTICATIONFAILED] Invalid\r\n"),
+            Some((
+                "Error",
+                "IMAP a001 NO [AUTHENTICATIONFAILED] Invalid".to_string()
+            ))
+        );
+        assert_eq!(
+            parse_imap_response(b"* OK IMAP4rev1 ready\r\n"),
+            Some(("IMAP", "IMAP * OK IMAP4rev1 ready".to_string()))
+        );
+        assert!(parse_imap_response(b"a001 LOGIN bob secret\r\n").is_none());
+    }
+}
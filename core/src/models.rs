@@ -1,28 +1,122 @@
-use serde::Serialize;
+use serde::{Serialize, Serializer};
 
+use crate::tcp::TcpOptions;
+
+/// Per-packet display fields, serialized inline with the rest of `Packet`.
 #[derive(Serialize)]
 pub struct PacketSummary {
     pub info: String,
     pub summary: String,
-    pub time: String,
-    pub src: String,
-    pub dst: String,
-    pub protocol: String,
-    pub length: usize,
+    /// Colon-separated protocol path, e.g. `eth:ip:tcp:http`.
+    pub protocol_stack: String,
+    /// 1-based position of the packet in the original capture.
+    pub frame_number: usize,
+    pub direction: Option<Direction>,
+    /// Capture time as integer nanoseconds since the Unix epoch, when
+    /// `ns_timestamps` is set. Emitted as a decimal string because present
+    /// day values exceed the integers a JavaScript number holds exactly.
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_decimal"
+    )]
+    pub epoch_ns: Option<i64>,
+    /// Options decoded from a TCP header, when it carried any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tcp_options: Option<TcpOptions>,
+    /// Shannon entropy of the frame in bits per byte, when `compute_entropy`
+    /// is set and the frame has at least `MIN_ENTROPY_SAMPLE` bytes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entropy: Option<f64>,
+    /// Name of the pcapng interface that captured the packet (`if_name`, or
+    /// `if{id}` when the capture does not name it).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub interface: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub interface_description: Option<String>,
     pub hex_preview: String,
     pub ascii_preview: String,
 }
 
+fn serialize_decimal<S: Serializer>(value: &Option<i64>, serializer: S) -> Result<S::Ok, S::Error> {
+    match value {
+        Some(value) => serializer.collect_str(value),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// Whether a packet was received or transmitted by the capturing interface.
+#[derive(Serialize, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum Direction {
+    Inbound,
+    Outbound,
+}
+
 #[derive(Serialize)]
 pub struct Packet {
     pub layers: Option<DecodedLayers>,
     pub time: String,
+    /// Whole seconds of `time`, unset for inputs that carry no timestamps.
+    #[serde(skip)]
+    pub epoch_seconds: Option<i64>,
     pub source: String,
     pub destination: String,
     pub protocol: String,
     pub length: usize,
-    pub info: String,
+    /// TCP sequence and acknowledgment numbers, `null` for other packets.
+    pub tcp_seq: Option<u32>,
+    pub tcp_ack: Option<u32>,
+    #[serde(flatten)]
+    pub info: PacketSummary,
+    /// The captured frame, link-layer header included. For the fragment that
+    /// completes a reassembled IPv4 datagram this is still the fragment's own
+    /// frame, while `layers` describe the whole datagram.
+    pub payload: Vec<u8>,
+    /// Index into `payload` where the network-layer header starts, `null`
+    /// when the link layer is unknown or carries no network protocol.
+    pub l3_offset: Option<usize>,
+    /// `opt_comment` annotations of a pcapng packet, joined by newlines;
+    /// `null` when the packet has none.
+    pub comment: Option<String>,
+}
+
+/// Flat packet shape with a single authoritative `summary` field, emitted by
+/// `process_packet_compact`.
+#[derive(Serialize)]
+pub struct CompactPacket {
+    pub frame_number: usize,
+    pub time: String,
+    pub source: String,
+    pub destination: String,
+    pub protocol: String,
+    pub length: usize,
+    pub summary: String,
+    pub hex_preview: String,
+    pub ascii_preview: String,
+    pub layers: Option<DecodedLayers>,
     pub payload: Vec<u8>,
+    pub l3_offset: Option<usize>,
+    pub comment: Option<String>,
+}
+
+impl From<Packet> for CompactPacket {
+    fn from(packet: Packet) -> Self {
+        CompactPacket {
+            frame_number: packet.info.frame_number,
+            time: packet.time,
+            source: packet.source,
+            destination: packet.destination,
+            protocol: packet.protocol,
+            length: packet.length,
+            summary: packet.info.summary,
+            hex_preview: packet.info.hex_preview,
+            ascii_preview: packet.info.ascii_preview,
+            layers: packet.layers,
+            payload: packet.payload,
+            l3_offset: packet.l3_offset,
+            comment: packet.comment,
+        }
+    }
 }
 
 #[derive(Serialize, Clone)]
@@ -30,6 +124,50 @@ pub struct EthernetHeader {
     pub source_mac: String,
     pub destination_mac: String,
     pub ethertype: u16,
+    /// 802.1Q/802.1ad tags, outermost first.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub vlan: Vec<VlanTag>,
+}
+/// One MPLS label stack entry.
+#[derive(Serialize, Clone)]
+pub struct MplsLabel {
+    pub label: u32,
+    pub traffic_class: u8,
+    pub ttl: u8,
+}
+/// An 802.1Q tag: the 12-bit VLAN ID and 3-bit priority code point.
+#[derive(Serialize, Clone, Copy)]
+pub struct VlanTag {
+    pub id: u16,
+    pub pcp: u8,
+}
+#[derive(Serialize, Clone)]
+pub struct ArpHeader {
+    pub operation: u16,
+    pub sender_mac: String,
+    pub sender_ip: String,
+    pub target_mac: String,
+    pub target_ip: String,
+}
+/// Target and link-layer address options of an IPv6 Neighbor Solicitation
+/// or Advertisement.
+#[derive(Serialize, Clone)]
+pub struct NdpHeader {
+    pub target: String,
+    pub source_link_layer: Option<String>,
+    pub target_link_layer: Option<String>,
+}
+/// Header fields, first question and rendered answers of a DNS message.
+#[derive(Serialize, Clone)]
+pub struct DnsMessage {
+    pub id: u16,
+    pub response: bool,
+    pub opcode: u8,
+    pub rcode: u8,
+    pub qname: Option<String>,
+    pub qtype: Option<String>,
+    pub qclass: Option<u16>,
+    pub answers: Vec<String>,
 }
 #[derive(Serialize, Clone)]
 pub struct Ipv4Header {
@@ -39,6 +177,8 @@ pub struct Ipv4Header {
     pub header_length: usize,
     pub total_length: usize,
     pub ttl: u8,
+    /// Whether the stored header checksum matches the computed one.
+    pub checksum_valid: bool,
 }
 #[derive(Serialize, Clone)]
 pub struct Ipv6Header {
@@ -52,6 +192,32 @@ pub struct Ipv6Header {
 pub struct TcpHeader {
     pub source_port: u16,
     pub destination_port: u16,
+    /// Sequence and acknowledgment numbers; unset when the captured header
+    /// is too short to hold them.
+    pub sequence: Option<u32>,
+    pub acknowledgment: Option<u32>,
+    pub flags: u8,
+    pub window: u16,
+    /// Shift count offered in this segment's window-scale option, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_scale: Option<u8>,
+    /// Receive window after applying the flow's negotiated scale factor.
+    pub effective_window: u32,
+    /// Maximum segment size announced by a SYN or SYN-ACK.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mss: Option<u16>,
+    /// Path MTU implied by `mss` for the carrying IP version.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path_mtu: Option<u32>,
+    /// Whether the SACK-permitted option was present.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub sack_permitted: bool,
+    /// TSval and TSecr from the timestamps option (RFC 7323).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timestamps: Option<(u32, u32)>,
+    /// Urgent pointer, set only when the URG flag marks out-of-band data.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub urgent_pointer: Option<u16>,
 }
 #[derive(Serialize, Clone)]
 pub struct UdpHeader {
@@ -70,11 +236,17 @@ pub struct IcmpHeader {
 #[derive(Serialize, Clone, Default)]
 pub struct DecodedLayers {
     pub ethernet: Option<EthernetHeader>,
+    /// MPLS label stack, top label first.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub mpls: Vec<MplsLabel>,
+    pub arp: Option<ArpHeader>,
     pub ipv4: Option<Ipv4Header>,
     pub ipv6: Option<Ipv6Header>,
     pub tcp: Option<TcpHeader>,
     pub udp: Option<UdpHeader>,
     pub icmp: Option<IcmpHeader>,
+    pub ndp: Option<NdpHeader>,
+    pub dns: Option<DnsMessage>,
 }
 
 #[derive(Serialize)]
@@ -82,16 +254,140 @@ pub struct PacketProcessingResult {
     pub packets: Vec<Packet>,
     pub warnings: Vec<String>,
     pub errors: Vec<String>,
+    pub capture_info: CaptureInfo,
+    /// IPv6 address to MAC pairs learned from neighbor discovery, sorted by
+    /// address.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub neighbor_cache: Vec<(String, String)>,
+}
+
+/// What a file is, as reported by `classify`. Fields that could not be
+/// determined are `null`.
+#[derive(Serialize)]
+pub struct Classification {
+    pub format: String,
+    pub linktype: Option<u32>,
+    pub linktype_name: Option<String>,
+    pub first_protocol: Option<String>,
+}
+
+/// One DNS message in the capture, as emitted by `extract_dns`.
+#[derive(Serialize)]
+pub struct DnsRecord {
+    pub time: String,
+    pub src: String,
+    pub dst: String,
+    pub qname: Option<String>,
+    pub qtype: Option<String>,
+    pub rcode: u8,
+    pub answers: Vec<String>,
+}
+
+/// One HTTP response body transferred in the capture, with the request
+/// that fetched it, as emitted by `extract_http_objects`.
+#[derive(Serialize)]
+pub struct HttpObject {
+    pub client: String,
+    pub server: String,
+    pub method: Option<String>,
+    pub uri: Option<String>,
+    pub status: u16,
+    pub content_type: Option<String>,
+    pub body_length: usize,
+    /// Base64 of the decoded body, only when bodies were requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+    /// Whether the body stopped early at a capture gap, the end of the
+    /// capture, or the per-flow reassembly limit.
+    pub truncated: bool,
+}
+
+/// Structural view of a pcapng file emitted by `dump_blocks`.
+#[derive(Serialize)]
+pub struct BlockDump {
+    pub blocks: Vec<BlockRecord>,
+    pub errors: Vec<String>,
+}
+
+/// One pcapng block as declared in the file, without dissecting its body.
+#[derive(Serialize)]
+pub struct BlockRecord {
+    pub offset: usize,
+    pub block_type: u32,
+    pub name: String,
+    pub length: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub interface_id: Option<u32>,
+}
+
+/// Capture-level provenance. Fields the capture does not record are omitted.
+#[derive(Serialize, Default)]
+pub struct CaptureInfo {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hardware: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub os: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub app: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub custom_options: Vec<CustomOption>,
+    /// Hex SHA-256 of the whole input, when `hash_file` is set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file_sha256: Option<String>,
+}
+
+/// A pcapng custom option, identified by its Private Enterprise Number.
+/// The vendor data itself is not interpreted; `count` is how many blocks
+/// carried an option with the same PEN and length.
+#[derive(Serialize)]
+pub struct CustomOption {
+    pub pen: u32,
+    pub length: usize,
+    pub count: usize,
+}
+
+#[derive(Serialize)]
+pub struct CompactProcessingResult {
+    pub packets: Vec<CompactPacket>,
+    pub warnings: Vec<String>,
+    pub errors: Vec<String>,
+    pub capture_info: CaptureInfo,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub neighbor_cache: Vec<(String, String)>,
+}
+
+impl From<PacketProcessingResult> for CompactProcessingResult {
+    fn from(result: PacketProcessingResult) -> Self {
+        CompactProcessingResult {
+            packets: result
+                .packets
+                .into_iter()
+                .map(CompactPacket::from)
+                .collect(),
+            warnings: result.warnings,
+            errors: result.errors,
+            capture_info: result.capture_info,
+            neighbor_cache: result.neighbor_cache,
+        }
+    }
 }
 
 pub struct PacketMetadata {
     pub layers: Option<DecodedLayers>,
     pub time: String,
+    pub epoch_seconds: Option<i64>,
     pub source: String,
     pub destination: String,
     pub protocol: String,
     pub summary: String,
     pub length: usize,
+    pub frame_number: usize,
+    pub direction: Option<Direction>,
+    pub epoch_ns: Option<i64>,
+    pub l3_offset: Option<usize>,
+    pub interface: Option<String>,
+    pub interface_description: Option<String>,
+    pub comment: Option<String>,
 }
 
 #[derive(Default)]
@@ -0,0 +1,47 @@
+use crate::models::MplsLabel;
+
+/// Label stack entries decoded before a frame is treated as malformed.
+const MAX_LABELS: usize = 16;
+
+/// Walks an MPLS label stack, returning its entries (top first) and the
+/// payload after the bottom-of-stack entry. The payload is `None` when the
+/// data ends, or the depth cap is reached, before an entry with S=1.
+pub fn parse_mpls_stack(mut data: &[u8]) -> (Vec<MplsLabel>, Option<&[u8]>) {
+    let mut labels = Vec::new();
+    while labels.len() < MAX_LABELS
+        && let Some(entry) = data.get(..4)
+    {
+        let entry = u32::from_be_bytes([entry[0], entry[1], entry[2], entry[3]]);
+        let bottom = entry & 0x100 != 0;
+        labels.push(MplsLabel {
+            label: entry >> 12,
+            traffic_class: ((entry >> 9) & 0x07) as u8,
+            ttl: entry as u8,
+        });
+        data = &data[4..];
+        if bottom {
+            return (labels, Some(data));
+        }
+    }
+    (labels, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn stops_at_bottom_of_stack() {
+        // Label 16000 (TC 5), then label 3 with S=1.
+        let data = [0x03, 0xE8, 0x0A, 0x40, 0x00, 0x00, 0x31, 0x3F, 0x45];
+        let (labels, payload) = parse_mpls_stack(&data);
+        assert_eq!(labels.len(), 2);
+        assert_eq!(labels[0].label, 16000);
+        assert_eq!(labels[0].traffic_class, 5);
+        assert_eq!(labels[1].label, 3);
+        assert_eq!(payload, Some(&[0x45][..]));
+
+        let (labels, payload) = parse_mpls_stack(&data[..4]);
+        assert_eq!(labels.len(), 1);
+        assert!(payload.is_none());
+    }
+}
@@ -0,0 +1,53 @@
+use std::collections::HashMap;
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use pcap_parser::pcapng::{NameRecordType, NameResolutionBlock};
+
+use crate::geo::network_addresses;
+use crate::models::Packet;
+
+/// Adds the IPv4 and IPv6 records of a pcapng Name Resolution Block to
+/// `names`, keyed by address text. Each record holds an address followed by
+/// NUL-terminated names; the first name is used and earlier entries win.
+pub fn collect_name_records(nrb: &NameResolutionBlock<'_>, names: &mut HashMap<String, String>) {
+    for record in &nrb.nr {
+        let value = record.record_value;
+        let (address, rest) = match record.record_type {
+            NameRecordType::End => break,
+            NameRecordType::Ipv4 if value.len() > 4 => {
+                let octets: [u8; 4] = value[..4].try_into().unwrap_or_default();
+                (Ipv4Addr::from(octets).to_string(), &value[4..])
+            }
+            NameRecordType::Ipv6 if value.len() > 16 => {
+                let octets: [u8; 16] = value[..16].try_into().unwrap_or_default();
+                (Ipv6Addr::from(octets).to_string(), &value[16..])
+            }
+            _ => continue,
+        };
+        let name = rest.split(|byte| *byte == 0).next().unwrap_or_default();
+        if !name.is_empty() {
+            names
+                .entry(address)
+                .or_insert_with(|| String::from_utf8_lossy(name).into_owned());
+        }
+    }
+}
+
+/// Rewrites packet endpoints whose IP has a recorded name as
+/// `server.local (10.0.0.1:80)`.
+pub fn apply_host_names(packets: &mut [Packet], names: &HashMap<String, String>) {
+    if names.is_empty() {
+        return;
+    }
+    for packet in packets {
+        let Some((source_ip, destination_ip)) = network_addresses(packet) else {
+            continue;
+        };
+        if let Some(name) = names.get(&source_ip) {
+            packet.source = format!("{name} ({})", packet.source);
+        }
+        if let Some(name) = names.get(&destination_ip) {
+            packet.destination = format!("{name} ({})", packet.destination);
+        }
+    }
+}
@@ -0,0 +1,81 @@
+/// Returns the operation and decoded NetBIOS name of an NBNS packet, such as
+/// `NBNS query WORKGROUP<00>`.
+pub fn parse_nbns(payload: &[u8]) -> Option<String> {
+    if payload.len() < 12 {
+        return None;
+    }
+    let flags = u16::from_be_bytes([payload[2], payload[3]]);
+    let operation = match (flags >> 11) & 0x0F {
+        0 => "query",
+        5 => "registration",
+        6 => "release",
+        7 => "wait",
+        8 | 9 => "refresh",
+        _ => return None,
+    };
+    let name = decode_netbios_name(&payload[12..])?;
+    let kind = if flags & 0x8000 != 0 { " response" } else { "" };
+    Some(format!("NBNS {operation}{kind} {name}"))
+}
+
+/// Decodes a first-level encoded NetBIOS name (RFC 1001 §14.1): 32 letters
+/// `A`–`P`, each carrying one nibble of the 16-byte padded name.
+fn decode_netbios_name(encoded: &[u8]) -> Option<String> {
+    if encoded.first() != Some(&32) {
+        return None;
+    }
+    let letters = encoded.get(1..33)?;
+    let mut bytes = Vec::with_capacity(16);
+    for pair in letters.chunks_exact(2) {
+        let high = pair[0].checked_sub(b'A').filter(|nibble| *nibble < 16)?;
+        let low = pair[1].checked_sub(b'A').filter(|nibble| *nibble < 16)?;
+        bytes.push((high << 4) | low);
+    }
+    let suffix = bytes[15];
+    let name = &bytes[..15];
+    if !name
+        .iter()
+        .all(|byte| byte.is_ascii_graphic() || *byte == b' ')
+    {
+        return None;
+    }
+    let name = String::from_utf8_lossy(name);
+    Some(format!("{}<{suffix:02X}>", name.trim_end()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(name: &str, suffix: u8) -> Vec<u8> {
+        let mut padded = format!("{name:<15}").into_bytes();
+        padded.push(suffix);
+        let mut encoded = vec![32];
+        for byte in padded {
+            encoded.push(b'A' + (byte >> 4));
+            encoded.push(b'A' + (byte & 0x0F));
+        }
+        encoded.push(0);
+        encoded
+    }
+
+    #[test]
+    fn decodes_query_name() {
+        let mut packet = vec![0x12, 0x34, 0x01, 0x10, 0, 1, 0, 0, 0, 0, 0, 0];
+        packet.extend_from_slice(&encode("WORKGROUP", 0));
+        packet.extend_from_slice(&[0, 0x20, 0, 1]);
+        assert_eq!(
+            parse_nbns(&packet).as_deref(),
+            Some("NBNS query WORKGROUP<00>")
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_names() {
+        let mut packet = vec![0; 12];
+        packet.extend_from_slice(&encode("HOST", 0x20));
+        packet[14] = b'Z';
+        assert!(parse_nbns(&packet).is_none());
+        assert!(parse_nbns(&packet[..20]).is_none());
+    }
+}
@@ -0,0 +1,93 @@
+use std::collections::BTreeMap;
+use std::net::Ipv6Addr;
+
+use crate::format_mac;
+use crate::models::{NdpHeader, PacketAnalysis};
+
+const SOURCE_LINK_LAYER: u8 = 1;
+const TARGET_LINK_LAYER: u8 = 2;
+
+/// Decodes the target address and link-layer address options of an ICMPv6
+/// Neighbor Solicitation (135) or Advertisement (136) message.
+pub fn parse_neighbor_discovery(message: &[u8]) -> Option<NdpHeader> {
+    if !matches!(message.first(), Some(135 | 136)) {
+        return None;
+    }
+    let target: [u8; 16] = message.get(8..24)?.try_into().ok()?;
+    let mut header = NdpHeader {
+        target: Ipv6Addr::from(target).to_string(),
+        source_link_layer: None,
+        target_link_layer: None,
+    };
+    let mut options = &message[24..];
+    while options.len() >= 8 {
+        let length = options[1] as usize * 8;
+        if length == 0 || length > options.len() {
+            break;
+        }
+        // Only Ethernet-sized addresses are meaningful for the cache.
+        if length == 8 {
+            let address = Some(format_mac(&options[2..8]));
+            match options[0] {
+                SOURCE_LINK_LAYER => header.source_link_layer = address,
+                TARGET_LINK_LAYER => header.target_link_layer = address,
+                _ => {}
+            }
+        }
+        options = &options[length..];
+    }
+    Some(header)
+}
+
+/// IPv6 to link-layer address mappings learned from neighbor discovery,
+/// the IPv6 counterpart of an ARP table.
+#[derive(Default)]
+pub struct NeighborCache {
+    entries: BTreeMap<Ipv6Addr, String>,
+}
+
+impl NeighborCache {
+    /// Learns the advertised target's address from a Neighbor Advertisement
+    /// and the solicitor's address from a Neighbor Solicitation.
+    pub fn observe(&mut self, analysis: &PacketAnalysis) {
+        let Some(ndp) = &analysis.layers.ndp else {
+            return;
+        };
+        if let (Some(mac), Ok(target)) = (&ndp.target_link_layer, ndp.target.parse()) {
+            self.entries.insert(target, mac.clone());
+        }
+        if let (Some(mac), Some(ipv6)) = (&ndp.source_link_layer, &analysis.layers.ipv6)
+            && let Ok(source) = ipv6.source.parse::<Ipv6Addr>()
+            && !source.is_unspecified()
+        {
+            self.entries.insert(source, mac.clone());
+        }
+    }
+
+    /// Returns the mappings ordered by address.
+    pub fn entries(&self) -> Vec<(String, String)> {
+        self.entries
+            .iter()
+            .map(|(address, mac)| (address.to_string(), mac.clone()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn reads_target_link_layer_option() {
+        let mut message = vec![136, 0, 0, 0, 0x60, 0, 0, 0];
+        message.extend_from_slice(&Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 2).octets());
+        message.extend_from_slice(&[2, 1, 0x00, 0x11, 0x22, 0x33, 0x44, 0x55]);
+        let header = parse_neighbor_discovery(&message).unwrap();
+        assert_eq!(header.target, "fe80::2");
+        assert_eq!(
+            header.target_link_layer.as_deref(),
+            Some("00:11:22:33:44:55")
+        );
+        assert!(header.source_link_layer.is_none());
+        assert!(parse_neighbor_discovery(&message[..20]).is_none());
+    }
+}
@@ -0,0 +1,116 @@
+use serde::Deserialize;
+
+/// Caller-tunable processing behavior, deserialized from the JSON accepted by
+/// `process_packet_with_options`. Omitted fields keep their defaults.
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct ProcessOptions {
+    /// Preview the last bytes of each payload instead of the first.
+    pub preview_from_end: bool,
+    /// Bytes shown in the hex and ASCII previews. Zero disables previews.
+    pub preview_len: usize,
+    /// Number of bytes printed without separators in the hex preview.
+    /// Zero and one both space every byte.
+    pub hex_group_size: usize,
+    /// Skip guessing IPv4/IPv6 from the version nibble on unknown linktypes.
+    pub disable_ip_guess: bool,
+    /// Upper bound on the payload bytes stored per packet. Dissection and
+    /// previews still see the whole payload. `None` keeps everything.
+    pub max_payload_bytes: Option<usize>,
+    /// Write `->` and `-` instead of the Unicode arrow and em-dash in
+    /// summaries and address placeholders.
+    pub ascii_only: bool,
+    /// Record the SHA-256 of the input bytes in `capture_info`.
+    pub hash_file: bool,
+    /// Ethernet frames longer than this many bytes (header included) are
+    /// marked `[jumbo]`.
+    pub jumbo_frame_threshold: usize,
+    /// Add `epoch_ns`, the capture time in integer nanoseconds, to each
+    /// packet summary.
+    pub ns_timestamps: bool,
+    /// Emit only the first packet of each direction-normalized 5-tuple,
+    /// noting how many packets the flow had.
+    pub first_per_flow: bool,
+    /// Keep only IPv4 (`4`) or IPv6 (`6`) packets; other packets, non-IP
+    /// included, are dropped right after dissection. `None` keeps all.
+    pub ip_version: Option<u8>,
+    /// Check IPv4 header checksums, marking mismatches `[bad checksum]` and
+    /// warning about them. Off by default because captures taken on hosts
+    /// with checksum offload routinely carry wrong values.
+    pub verify_ipv4_checksum: bool,
+    /// Tunnel layers (GRE, IP-in-IP, MPLS, CAPWAP) unwrapped before
+    /// dissection stops with `[max decapsulation depth reached]`.
+    pub max_decap_depth: usize,
+    /// Add the Shannon entropy of each frame to its summary, marking
+    /// `[high entropy]` ones that look encrypted or compressed.
+    pub compute_entropy: bool,
+    /// When the input is not recognized as pcap or pcapng, retry both with
+    /// the magic number repaired and keep whichever dissects the most
+    /// packets. Off by default since it triples the work on raw input.
+    pub try_all_formats: bool,
+}
+
+impl Default for ProcessOptions {
+    fn default() -> Self {
+        ProcessOptions {
+            preview_from_end: false,
+            preview_len: 32,
+            hex_group_size: 0,
+            disable_ip_guess: false,
+            max_payload_bytes: None,
+            ascii_only: false,
+            hash_file: false,
+            jumbo_frame_threshold: 1518,
+            ns_timestamps: false,
+            first_per_flow: false,
+            ip_version: None,
+            verify_ipv4_checksum: false,
+            max_decap_depth: 8,
+            compute_entropy: false,
+            try_all_formats: false,
+        }
+    }
+}
+
+impl ProcessOptions {
+    pub fn from_json(json: &str) -> Result<ProcessOptions, String> {
+        if json.trim().is_empty() {
+            return Ok(ProcessOptions::default());
+        }
+        let options: ProcessOptions = serde_json::from_str(json)
+            .map_err(|err| format!("Invalid processing options: {err}"))?;
+        if let Some(version) = options.ip_version
+            && version != 4
+            && version != 6
+        {
+            return Err(format!(
+                "Invalid processing options: ip_version must be 4 or 6, got {version}"
+            ));
+        }
+        Ok(options)
+    }
+
+    /// Whether a packet of this IP version (`None` for non-IP) passes the
+    /// `ip_version` filter.
+    pub fn keeps_ip_version(&self, version: Option<u8>) -> bool {
+        match self.ip_version {
+            None => true,
+            Some(4) => version == Some(4),
+            Some(6) => version == Some(6),
+            Some(_) => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn missing_fields_use_defaults() {
+        let options = ProcessOptions::from_json("{}").unwrap();
+        assert!(!options.preview_from_end);
+        assert_eq!(options.jumbo_frame_threshold, 1518);
+        assert!(ProcessOptions::from_json("{\"preview_from_end\": 1}").is_err());
+        assert!(ProcessOptions::from_json("{\"ip_version\": 5}").is_err());
+    }
+}
@@ -0,0 +1,74 @@
+use std::net::Ipv4Addr;
+
+/// Summarizes an OSPFv2 or OSPFv3 packet, listing neighbors for Hello packets.
+pub fn parse_ospf(payload: &[u8]) -> Option<String> {
+    if payload.len() < 4 {
+        return None;
+    }
+    let (header_len, hello_len) = match payload[0] {
+        2 => (24, 20),
+        3 => (16, 20),
+        _ => return None,
+    };
+    if payload.len() < header_len {
+        return None;
+    }
+    let kind = match payload[1] {
+        1 => "Hello",
+        2 => "DB Description",
+        3 => "LS Request",
+        4 => "LS Update",
+        5 => "LS Acknowledge",
+        _ => return None,
+    };
+    let router = read_ipv4(&payload[4..8]);
+    let area = u32::from_be_bytes([payload[8], payload[9], payload[10], payload[11]]);
+    let mut summary = format!("OSPFv{} {kind} router={router} area={area}", payload[0]);
+
+    if payload[1] == 1 {
+        let declared = u16::from_be_bytes([payload[2], payload[3]]) as usize;
+        let end = declared.min(payload.len());
+        let start = header_len + hello_len;
+        if end > start {
+            let neighbors = payload[start..end]
+                .chunks_exact(4)
+                .map(read_ipv4)
+                .map(|addr| addr.to_string())
+                .collect::<Vec<_>>();
+            if !neighbors.is_empty() {
+                summary.push_str(&format!(" neighbors={}", neighbors.join(",")));
+            }
+        }
+    }
+    Some(summary)
+}
+
+fn read_ipv4(bytes: &[u8]) -> Ipv4Addr {
+    Ipv4Addr::new(bytes[0], bytes[1], bytes[2], bytes[3])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn summarizes_hello_with_neighbors() {
+        let mut packet = vec![2, 1, 0, 48, 1, 1, 1, 1, 0, 0, 0, 0];
+        packet.extend_from_slice(&[0; 12]);
+        packet.extend_from_slice(&[0; 20]);
+        packet.extend_from_slice(&[2, 2, 2, 2]);
+        assert_eq!(
+            parse_ospf(&packet).as_deref(),
+            Some("OSPFv2 Hello router=1.1.1.1 area=0 neighbors=2.2.2.2")
+        );
+    }
+    #[test]
+    fn neighbor_list_is_bounded_by_payload() {
+        let mut packet = vec![2, 1, 0xFF, 0xFF, 1, 1, 1, 1, 0, 0, 0, 1];
+        packet.extend_from_slice(&[0; 12]);
+        packet.extend_from_slice(&[0; 10]);
+        assert_eq!(
+            parse_ospf(&packet).as_deref(),
+            Some("OSPFv2 Hello router=1.1.1.1 area=1")
+        );
+    }
+}
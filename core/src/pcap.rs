@@ -30,6 +30,25 @@ pub struct PcapHeaderInfo {
     pub _snaplen: u32,
 }
 
+/// Returns the `LINKTYPE_` name (without the prefix) for linktypes the
+/// dissector knows, or `UNKNOWN`.
+pub fn linktype_name(linktype: u32) -> &'static str {
+    match linktype {
+        0 => "NULL",
+        1 => "ETHERNET",
+        9 => "PPP",
+        101 => "RAW",
+        105 => "IEEE802_11",
+        113 => "LINUX_SLL",
+        127 => "IEEE802_11_RADIOTAP",
+        147..=162 => "USER",
+        228 => "IPV4",
+        229 => "IPV6",
+        276 => "LINUX_SLL2",
+        _ => "UNKNOWN",
+    }
+}
+
 pub fn parse_pcap_header(data: &[u8]) -> Result<(PcapHeaderInfo, usize), String> {
     if data.len() < 24 {
         return Err("PCAP data is too short".to_string());
@@ -1 +1,229 @@
-// pcapng module placeholder for parser-focused helpers
+// Parser-focused helpers for pcapng block options.
+
+use pcap_parser::pcapng::{Block, OptionCode, PcapNGOption};
+
+use crate::models::{BlockRecord, CaptureInfo, CustomOption, Direction};
+
+const EPB_FLAGS: OptionCode = OptionCode(2);
+const IF_NAME: OptionCode = OptionCode(2);
+const IF_DESCRIPTION: OptionCode = OptionCode(3);
+const ISB_IFRECV: OptionCode = OptionCode(4);
+const ISB_IFDROP: OptionCode = OptionCode(5);
+const CUSTOM_OPTIONS: [OptionCode; 4] = [
+    OptionCode::Custom2988,
+    OptionCode::Custom2989,
+    OptionCode::Custom19372,
+    OptionCode::Custom19373,
+];
+/// Distinct custom options kept in the capture info. Every packet block may
+/// carry them, so repeats are counted and new ones past this are dropped.
+const MAX_CUSTOM_OPTIONS: usize = 64;
+
+/// Decoded `epb_flags` word of an Enhanced Packet Block.
+pub struct EpbFlags {
+    pub direction: Option<Direction>,
+    pub reception: Option<&'static str>,
+    pub fcs_length: u8,
+}
+
+/// Returns the option value without the trailing 32-bit alignment padding.
+pub fn option_value<'a>(option: &PcapNGOption<'a>) -> &'a [u8] {
+    let len = (option.len as usize).min(option.value.len());
+    &option.value[..len]
+}
+
+/// Decodes a UTF-8 option value, replacing invalid sequences and dropping
+/// any trailing NUL terminators some writers include.
+pub fn option_string(option: &PcapNGOption<'_>) -> String {
+    String::from_utf8_lossy(option_value(option))
+        .trim_end_matches('\0')
+        .to_string()
+}
+
+/// Records the capturing hardware, OS and application from a Section Header
+/// Block, keeping values already seen in an earlier section.
+pub fn apply_section_options(info: &mut CaptureInfo, options: &[PcapNGOption<'_>]) {
+    for option in options {
+        let field = match option.code {
+            OptionCode::ShbHardware => &mut info.hardware,
+            OptionCode::ShbOs => &mut info.os,
+            OptionCode::ShbUserAppl => &mut info.app,
+            _ => continue,
+        };
+        if field.is_none() {
+            *field = Some(option_string(option));
+        }
+    }
+}
+
+/// Returns the `if_name` and `if_description` options of an Interface
+/// Description Block, when present.
+pub fn interface_labels(options: &[PcapNGOption<'_>]) -> (Option<String>, Option<String>) {
+    let find = |code: OptionCode| {
+        options
+            .iter()
+            .find(|option| option.code == code)
+            .map(option_string)
+    };
+    (find(IF_NAME), find(IF_DESCRIPTION))
+}
+
+/// Joins the `opt_comment` options of a packet block, one per line.
+pub fn packet_comment(options: &[PcapNGOption<'_>]) -> Option<String> {
+    let comments: Vec<String> = options
+        .iter()
+        .filter(|option| option.code == OptionCode::Comment)
+        .map(option_string)
+        .collect();
+    (!comments.is_empty()).then(|| comments.join("\n"))
+}
+
+/// Notes every custom option in `options` by enterprise number and vendor
+/// data length.
+pub fn collect_custom_options(
+    info: &mut CaptureInfo,
+    options: &[PcapNGOption<'_>],
+    big_endian: bool,
+) {
+    for option in options {
+        if !CUSTOM_OPTIONS.contains(&option.code) {
+            continue;
+        }
+        let value = option_value(option);
+        let Some(pen) = read_option_u32(value, big_endian) else {
+            continue;
+        };
+        let length = value.len() - 4;
+        if let Some(seen) = info
+            .custom_options
+            .iter_mut()
+            .find(|seen| seen.pen == pen && seen.length == length)
+        {
+            seen.count += 1;
+        } else if info.custom_options.len() < MAX_CUSTOM_OPTIONS {
+            info.custom_options.push(CustomOption {
+                pen,
+                length,
+                count: 1,
+            });
+        }
+    }
+}
+
+pub fn read_option_u32(value: &[u8], big_endian: bool) -> Option<u32> {
+    let bytes: [u8; 4] = value.get(..4)?.try_into().ok()?;
+    Some(if big_endian {
+        u32::from_be_bytes(bytes)
+    } else {
+        u32::from_le_bytes(bytes)
+    })
+}
+
+fn read_option_u64(value: &[u8], big_endian: bool) -> Option<u64> {
+    let bytes: [u8; 8] = value.get(..8)?.try_into().ok()?;
+    Some(if big_endian {
+        u64::from_be_bytes(bytes)
+    } else {
+        u64::from_le_bytes(bytes)
+    })
+}
+
+/// Reads the `isb_ifrecv` and `isb_ifdrop` counters of an Interface
+/// Statistics Block, returned as (received, dropped).
+pub fn interface_counters(
+    options: &[PcapNGOption<'_>],
+    big_endian: bool,
+) -> (Option<u64>, Option<u64>) {
+    let read = |code: OptionCode| {
+        options
+            .iter()
+            .find(|option| option.code == code)
+            .and_then(|option| read_option_u64(option_value(option), big_endian))
+    };
+    (read(ISB_IFRECV), read(ISB_IFDROP))
+}
+
+pub fn parse_epb_flags(options: &[PcapNGOption<'_>], big_endian: bool) -> Option<EpbFlags> {
+    let option = options.iter().find(|option| option.code == EPB_FLAGS)?;
+    let flags = read_option_u32(option_value(option), big_endian)?;
+    let direction = match flags & 0b11 {
+        1 => Some(Direction::Inbound),
+        2 => Some(Direction::Outbound),
+        _ => None,
+    };
+    let reception = match (flags >> 2) & 0b111 {
+        1 => Some("unicast"),
+        2 => Some("multicast"),
+        3 => Some("broadcast"),
+        4 => Some("promiscuous"),
+        _ => None,
+    };
+    Some(EpbFlags {
+        direction,
+        reception,
+        fcs_length: ((flags >> 5) & 0x0F) as u8,
+    })
+}
+
+/// Describes a block by its type, declared length, and (for packet blocks)
+/// interface id. Types without a dedicated parser are named by number.
+pub fn describe_block(block: &Block, offset: usize) -> BlockRecord {
+    let (name, length, interface_id) = match block {
+        Block::SectionHeader(b) => ("SHB", b.block_len1, None),
+        Block::InterfaceDescription(b) => ("IDB", b.block_len1, None),
+        Block::EnhancedPacket(b) => ("EPB", b.block_len1, Some(b.if_id)),
+        Block::SimplePacket(b) => ("SPB", b.block_len1, None),
+        Block::NameResolution(b) => ("NRB", b.block_len1, None),
+        Block::InterfaceStatistics(b) => ("ISB", b.block_len1, Some(b.if_id)),
+        Block::SystemdJournalExport(b) => ("SJE", b.block_len1, None),
+        Block::DecryptionSecrets(b) => ("DSB", b.block_len1, None),
+        Block::Custom(b) => ("Custom", b.block_len1, None),
+        Block::Unknown(b) => ("", b.block_len1, None),
+    };
+    let block_type = block.magic();
+    BlockRecord {
+        offset,
+        block_type,
+        name: if name.is_empty() {
+            format!("0x{block_type:08X}")
+        } else {
+            name.to_string()
+        },
+        length,
+        interface_id,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn decodes_outbound_broadcast_flags() {
+        let value = 0b0110_1110u32.to_le_bytes();
+        let options = [PcapNGOption {
+            code: EPB_FLAGS,
+            len: 4,
+            value: &value,
+        }];
+        let flags = parse_epb_flags(&options, false).unwrap();
+        assert!(matches!(flags.direction, Some(Direction::Outbound)));
+        assert_eq!(flags.reception, Some("broadcast"));
+        assert_eq!(flags.fcs_length, 3);
+    }
+    #[test]
+    fn reports_custom_option_presence() {
+        let value = [0, 0, 0x7E, 0x3A, 1, 2, 3, 0];
+        let options = [PcapNGOption {
+            code: OptionCode::Custom2988,
+            len: 7,
+            value: &value,
+        }];
+        let mut info = CaptureInfo::default();
+        collect_custom_options(&mut info, &options, true);
+        collect_custom_options(&mut info, &options, true);
+        assert_eq!(info.custom_options.len(), 1);
+        assert_eq!(info.custom_options[0].pen, 32314);
+        assert_eq!(info.custom_options[0].length, 3);
+        assert_eq!(info.custom_options[0].count, 2);
+    }
+}
@@ -0,0 +1,47 @@
+/// Splits a PPP frame into its protocol number and payload, accepting both
+/// the HDLC-like `FF 03` address/control prefix and its compressed form, as
+/// well as one-byte (field-compressed) protocol numbers.
+pub fn parse_ppp_header(frame: &[u8]) -> Option<(u16, &[u8])> {
+    let frame = frame.strip_prefix(&[0xFF, 0x03]).unwrap_or(frame);
+    let first = *frame.first()?;
+    // Protocol numbers are odd in their low byte; an odd first byte means the
+    // leading zero byte was compressed away.
+    if first & 1 == 1 {
+        return Some((u16::from(first), &frame[1..]));
+    }
+    let second = *frame.get(1)?;
+    if second & 1 == 0 {
+        return None;
+    }
+    Some((u16::from_be_bytes([first, second]), &frame[2..]))
+}
+
+/// Names the PPP control and data protocols seen in VPN captures.
+pub fn ppp_protocol_name(protocol: u16) -> Option<&'static str> {
+    Some(match protocol {
+        0x0021 => "IPv4",
+        0x0057 => "IPv6",
+        0x00FD => "compressed data",
+        0x8021 => "IPCP",
+        0x8057 => "IPv6CP",
+        0x80FD => "CCP",
+        0xC021 => "LCP",
+        0xC023 => "PAP",
+        0xC223 => "CHAP",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn handles_compressed_headers() {
+        assert_eq!(
+            parse_ppp_header(&[0xFF, 0x03, 0xC0, 0x21, 1]),
+            Some((0xC021, &[1][..]))
+        );
+        assert_eq!(parse_ppp_header(&[0x21, 0x45]), Some((0x0021, &[0x45][..])));
+        assert!(parse_ppp_header(&[0xC0, 0x20]).is_none());
+    }
+}
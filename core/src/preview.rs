@@ -1,20 +1,51 @@
-pub fn build_hex_preview(bytes: &[u8], max_len: usize) -> String {
-    let preview_len = bytes.len().min(max_len);
-    let mut parts = Vec::with_capacity(preview_len);
-    for byte in bytes.iter().take(preview_len) {
-        parts.push(format!("{:02X}", byte));
+/// Uppercase hex digits for every byte value, so rendering a preview needs
+/// no per-byte formatting.
+static HEX_TABLE: [[u8; 2]; 256] = {
+    const DIGITS: &[u8; 16] = b"0123456789ABCDEF";
+    let mut table = [[0u8; 2]; 256];
+    let mut byte = 0;
+    while byte < 256 {
+        table[byte] = [DIGITS[byte >> 4], DIGITS[byte & 0x0F]];
+        byte += 1;
+    }
+    table
+};
+
+/// Renders bytes as hex, separating groups of `group_size` bytes with a
+/// space. A group size of zero or one spaces every byte.
+pub fn build_hex_preview(
+    bytes: &[u8],
+    max_len: usize,
+    from_end: bool,
+    group_size: usize,
+) -> String {
+    let (window, truncated) = preview_window(bytes, max_len, from_end);
+    let group_size = group_size.max(1);
+    let mut preview = String::with_capacity(window.len() * 3 + 4);
+    if truncated && from_end {
+        preview.push_str("… ");
     }
-    let mut preview = parts.join(" ");
-    if bytes.len() > preview_len {
+    for (index, byte) in window.iter().enumerate() {
+        if index > 0 && index % group_size == 0 {
+            preview.push(' ');
+        }
+        let [high, low] = HEX_TABLE[*byte as usize];
+        preview.push(high as char);
+        preview.push(low as char);
+    }
+    if truncated && !from_end {
         preview.push_str(" …");
     }
     preview
 }
 
-pub fn build_ascii_preview(bytes: &[u8], max_len: usize) -> String {
-    let preview_len = bytes.len().min(max_len);
-    let mut preview = String::with_capacity(preview_len);
-    for byte in bytes.iter().take(preview_len) {
+pub fn build_ascii_preview(bytes: &[u8], max_len: usize, from_end: bool) -> String {
+    let (window, truncated) = preview_window(bytes, max_len, from_end);
+    let mut preview = String::with_capacity(window.len());
+    if truncated && from_end {
+        preview.push('…');
+    }
+    for byte in window {
         let ch = *byte;
         if (0x20..=0x7E).contains(&ch) {
             preview.push(ch as char);
@@ -22,21 +53,77 @@ pub fn build_ascii_preview(bytes: &[u8], max_len: usize) -> String {
             preview.push('.');
         }
     }
-    if bytes.len() > preview_len {
+    if truncated && !from_end {
         preview.push('…');
     }
     preview
 }
 
+/// Selects the bytes to preview and whether any were left out.
+fn preview_window(bytes: &[u8], max_len: usize, from_end: bool) -> (&[u8], bool) {
+    let preview_len = bytes.len().min(max_len);
+    let window = if from_end {
+        &bytes[bytes.len() - preview_len..]
+    } else {
+        &bytes[..preview_len]
+    };
+    (window, bytes.len() > preview_len)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     #[test]
     fn hex_preview_truncates() {
-        assert_eq!(build_hex_preview(&[0, 1, 2], 2), "00 01 …");
+        assert_eq!(build_hex_preview(&[0, 1, 2], 2, false, 1), "00 01 …");
+    }
+    #[test]
+    fn hex_table_matches_formatting() {
+        let bytes: Vec<u8> = (0..=255).collect();
+        let expected: Vec<String> = bytes.iter().map(|byte| format!("{byte:02X}")).collect();
+        assert_eq!(build_hex_preview(&bytes, 256, false, 1), expected.join(" "));
     }
     #[test]
     fn ascii_preview_maps_non_printable() {
-        assert_eq!(build_ascii_preview(&[65, 0, 66], 3), "A.B");
+        assert_eq!(build_ascii_preview(&[65, 0, 66], 3, false), "A.B");
+    }
+    #[test]
+    fn groups_hex_bytes() {
+        assert_eq!(
+            build_hex_preview(&[0, 0x11, 0x22, 0x33, 0x44], 5, false, 2),
+            "0011 2233 44"
+        );
+        assert_eq!(
+            build_hex_preview(&[0, 0x11, 0x22, 0x33, 0x44], 4, false, 2),
+            "0011 2233 …"
+        );
+    }
+    #[test]
+    fn previews_tail_with_leading_ellipsis() {
+        assert_eq!(build_hex_preview(&[0, 1, 2], 2, true, 1), "… 01 02");
+        assert_eq!(build_ascii_preview(&[65, 66, 67], 2, true), "…BC");
+    }
+    #[test]
+    fn exact_length_has_no_ellipsis_in_either_direction() {
+        for from_end in [false, true] {
+            assert_eq!(build_hex_preview(&[0, 1], 2, from_end, 0), "00 01");
+            assert_eq!(build_ascii_preview(&[65, 66], 2, from_end), "AB");
+        }
+    }
+    #[test]
+    fn ellipsis_appears_only_past_max_len() {
+        let max_len = 32;
+        for len in [max_len - 1, max_len, max_len + 1] {
+            let bytes = vec![b'A'; len];
+            let truncated = len > max_len;
+            for from_end in [false, true] {
+                let hex = build_hex_preview(&bytes, max_len, from_end, 1);
+                let ascii = build_ascii_preview(&bytes, max_len, from_end);
+                assert_eq!(hex.contains('…'), truncated, "hex len {len}");
+                assert_eq!(ascii.contains('…'), truncated, "ascii len {len}");
+                assert_eq!(hex.matches("41").count(), len.min(max_len));
+                assert_eq!(ascii.matches('A').count(), len.min(max_len));
+            }
+        }
     }
 }
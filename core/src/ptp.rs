@@ -0,0 +1,44 @@
+const HEADER_LEN: usize = 34;
+
+/// Summarizes a PTPv2 (IEEE 1588) message by type and domain, e.g.
+/// `PTP Sync domain=0`. Anything shorter than the common header or of
+/// another version returns `None`.
+pub fn parse_ptp(payload: &[u8]) -> Option<String> {
+    let header = payload.get(..HEADER_LEN)?;
+    if header[1] & 0x0F != 2 {
+        return None;
+    }
+    let kind = match header[0] & 0x0F {
+        0x0 => "Sync".to_string(),
+        0x1 => "Delay_Req".to_string(),
+        0x2 => "Pdelay_Req".to_string(),
+        0x3 => "Pdelay_Resp".to_string(),
+        0x8 => "Follow_Up".to_string(),
+        0x9 => "Delay_Resp".to_string(),
+        0xA => "Pdelay_Resp_Follow_Up".to_string(),
+        0xB => "Announce".to_string(),
+        0xC => "Signaling".to_string(),
+        0xD => "Management".to_string(),
+        other => format!("type {other}"),
+    };
+    Some(format!("PTP {kind} domain={}", header[4]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn names_message_type_and_domain() {
+        let mut announce = [0u8; 64];
+        announce[0] = 0x0B;
+        announce[1] = 0x02;
+        announce[4] = 24;
+        assert_eq!(
+            parse_ptp(&announce).as_deref(),
+            Some("PTP Announce domain=24")
+        );
+        assert!(parse_ptp(&announce[..20]).is_none());
+        announce[1] = 0x01;
+        assert!(parse_ptp(&announce).is_none());
+    }
+}
@@ -0,0 +1,217 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::internet_checksum;
+
+/// Largest datagram the IPv4 total-length field can describe.
+const MAX_DATAGRAM: usize = 65_535;
+/// Fragment chains buffered at once; fragments of further datagrams are
+/// left unreassembled so hostile captures cannot exhaust memory.
+const MAX_PENDING: usize = 1024;
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct FragmentKey {
+    source: [u8; 4],
+    destination: [u8; 4],
+    id: u16,
+    protocol: u8,
+}
+
+#[derive(Default)]
+struct FragmentChain {
+    /// Header of the first fragment, reused for the reassembled datagram.
+    header: Option<Vec<u8>>,
+    /// Fragment data keyed by byte offset within the datagram payload.
+    pieces: BTreeMap<usize, Vec<u8>>,
+    /// Payload length, known once the last fragment (MF clear) arrives.
+    total: Option<usize>,
+    /// Byte ranges received so far, merged, as start → end. Lets a
+    /// fragment be checked for completing the datagram without copying.
+    covered: BTreeMap<usize, usize>,
+}
+
+impl FragmentChain {
+    /// Stores a fragment, keeping the longer piece when one already starts
+    /// at the same offset so `covered` never claims bytes that are missing.
+    fn insert(&mut self, offset: usize, data: &[u8]) {
+        if self
+            .pieces
+            .get(&offset)
+            .is_some_and(|piece| piece.len() >= data.len())
+        {
+            return;
+        }
+        self.pieces.insert(offset, data.to_vec());
+        let (mut start, mut end) = (offset, offset + data.len());
+        if let Some((&before, &before_end)) = self.covered.range(..=start).next_back()
+            && before_end >= start
+        {
+            start = before;
+            end = end.max(before_end);
+        }
+        while let Some((&next, &next_end)) = self.covered.range(start..).next()
+            && next <= end
+        {
+            end = end.max(next_end);
+            self.covered.remove(&next);
+        }
+        self.covered.insert(start, end);
+    }
+
+    /// Whether the pieces cover the whole payload.
+    fn complete(&self) -> bool {
+        self.total
+            .is_some_and(|total| self.covered.get(&0).is_some_and(|&end| end >= total))
+    }
+
+    /// Returns the payload when the pieces cover it without gaps.
+    fn assemble(&self) -> Option<Vec<u8>> {
+        let total = self.total?;
+        let mut payload = Vec::with_capacity(total);
+        for (&offset, data) in &self.pieces {
+            if offset > payload.len() {
+                return None;
+            }
+            let skip = payload.len() - offset;
+            payload.extend_from_slice(data.get(skip..).unwrap_or(&[]));
+        }
+        (payload.len() >= total).then(|| {
+            payload.truncate(total);
+            payload
+        })
+    }
+}
+
+/// A datagram rebuilt from its fragments.
+pub struct Reassembled {
+    pub datagram: Vec<u8>,
+    pub fragments: usize,
+}
+
+/// Buffers IPv4 fragments keyed on (source, destination, ID, protocol)
+/// until every byte of the datagram has arrived.
+#[derive(Default)]
+pub struct Ipv4Reassembler {
+    chains: HashMap<FragmentKey, FragmentChain>,
+}
+
+impl Ipv4Reassembler {
+    /// Records `packet` if it is a fragment, returning the whole datagram
+    /// once this fragment completes it. Unfragmented packets are ignored.
+    pub fn push(&mut self, packet: &[u8]) -> Option<Reassembled> {
+        let header = packet.get(..20)?;
+        if header[0] >> 4 != 4 {
+            return None;
+        }
+        let ihl = (header[0] & 0x0F) as usize * 4;
+        let total_length = u16::from_be_bytes([header[2], header[3]]) as usize;
+        let flags_offset = u16::from_be_bytes([header[6], header[7]]);
+        let more_fragments = flags_offset & 0x2000 != 0;
+        let offset = (flags_offset & 0x1FFF) as usize * 8;
+        if !more_fragments && offset == 0 {
+            return None;
+        }
+        if ihl < 20 {
+            return None;
+        }
+        let data = packet.get(ihl..total_length.min(packet.len()))?;
+        if offset + data.len() > MAX_DATAGRAM - ihl {
+            return None;
+        }
+        let key = FragmentKey {
+            source: header[12..16].try_into().ok()?,
+            destination: header[16..20].try_into().ok()?,
+            id: u16::from_be_bytes([header[4], header[5]]),
+            protocol: header[9],
+        };
+        if !self.chains.contains_key(&key) && self.chains.len() >= MAX_PENDING {
+            return None;
+        }
+        let chain = self.chains.entry(key.clone()).or_default();
+        if offset == 0 {
+            chain.header = Some(packet[..ihl].to_vec());
+        }
+        if !more_fragments {
+            chain.total = Some(offset + data.len());
+        }
+        chain.insert(offset, data);
+        if !chain.complete() {
+            return None;
+        }
+        let payload = chain.assemble()?;
+        let mut datagram = chain.header.clone()?;
+        let fragments = chain.pieces.len();
+        self.chains.remove(&key);
+
+        let total = u16::try_from(datagram.len() + payload.len()).ok()?;
+        datagram[2..4].copy_from_slice(&total.to_be_bytes());
+        datagram[6..8].copy_from_slice(&[0, 0]);
+        datagram[10..12].copy_from_slice(&[0, 0]);
+        let checksum = internet_checksum(&datagram);
+        datagram[10..12].copy_from_slice(&checksum.to_be_bytes());
+        datagram.extend_from_slice(&payload);
+        Some(Reassembled {
+            datagram,
+            fragments,
+        })
+    }
+
+    /// Number of datagrams still missing fragments.
+    pub fn pending(&self) -> usize {
+        self.chains.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fragment(offset_words: u16, more: bool, data: &[u8]) -> Vec<u8> {
+        let mut packet = vec![0u8; 20];
+        packet[0] = 0x45;
+        packet[2..4].copy_from_slice(&((20 + data.len()) as u16).to_be_bytes());
+        packet[4..6].copy_from_slice(&0x1234u16.to_be_bytes());
+        let flags = if more { 0x2000 } else { 0 } | offset_words;
+        packet[6..8].copy_from_slice(&flags.to_be_bytes());
+        packet[9] = 17;
+        packet[12..16].copy_from_slice(&[10, 0, 0, 1]);
+        packet[16..20].copy_from_slice(&[10, 0, 0, 2]);
+        packet.extend_from_slice(data);
+        packet
+    }
+
+    #[test]
+    fn reassembles_out_of_order_fragments() {
+        let mut reassembler = Ipv4Reassembler::default();
+        assert!(reassembler.push(&fragment(1, false, &[9; 4])).is_none());
+        assert_eq!(reassembler.pending(), 1);
+        let whole = reassembler.push(&fragment(0, true, &[1; 8])).unwrap();
+        assert_eq!(whole.fragments, 2);
+        assert_eq!(whole.datagram.len(), 32);
+        assert_eq!(&whole.datagram[2..4], &[0, 32]);
+        assert_eq!(&whole.datagram[6..8], &[0, 0]);
+        assert_eq!(internet_checksum(&whole.datagram[..20]), 0);
+        assert_eq!(&whole.datagram[20..], &[1, 1, 1, 1, 1, 1, 1, 1, 9, 9, 9, 9]);
+        assert_eq!(reassembler.pending(), 0);
+    }
+
+    #[test]
+    fn waits_for_every_byte_after_the_last_fragment() {
+        let mut reassembler = Ipv4Reassembler::default();
+        assert!(reassembler.push(&fragment(3, false, &[4; 8])).is_none());
+        assert!(reassembler.push(&fragment(2, true, &[3; 8])).is_none());
+        // A shorter duplicate must not replace the piece already held.
+        assert!(reassembler.push(&fragment(2, true, &[3; 4])).is_none());
+        assert!(reassembler.push(&fragment(0, true, &[1; 8])).is_none());
+        let whole = reassembler.push(&fragment(1, true, &[2; 8])).unwrap();
+        assert_eq!(whole.fragments, 4);
+        assert_eq!(whole.datagram.len(), 52);
+        assert_eq!(&whole.datagram[36..44], &[3; 8]);
+    }
+
+    #[test]
+    fn ignores_unfragmented_packets() {
+        let mut reassembler = Ipv4Reassembler::default();
+        assert!(reassembler.push(&fragment(0, false, &[1; 8])).is_none());
+        assert_eq!(reassembler.pending(), 0);
+    }
+}
@@ -0,0 +1,63 @@
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+const INIT: u8 = 1;
+const INIT_ACK: u8 = 2;
+const IPV4_ADDRESS: u16 = 5;
+const IPV6_ADDRESS: u16 = 6;
+
+/// Returns the additional addresses an endpoint advertises for multihoming in
+/// the optional parameters of an INIT or INIT-ACK chunk. The parameter walk
+/// stays within the declared chunk length.
+pub fn init_addresses(packet: &[u8]) -> Vec<String> {
+    let mut addresses = Vec::new();
+    let Some(chunk) = packet.get(12..16) else {
+        return addresses;
+    };
+    if !matches!(chunk[0], INIT | INIT_ACK) {
+        return addresses;
+    }
+    let chunk_len = u16::from_be_bytes([chunk[2], chunk[3]]) as usize;
+    let Some(chunk) = packet.get(12..12 + chunk_len) else {
+        return addresses;
+    };
+    // Chunk header (4 bytes) plus the fixed INIT fields (16 bytes).
+    let mut params = chunk.get(20..).unwrap_or(&[]);
+    while params.len() >= 4 {
+        let param_type = u16::from_be_bytes([params[0], params[1]]);
+        let length = u16::from_be_bytes([params[2], params[3]]) as usize;
+        if length < 4 || length > params.len() {
+            break;
+        }
+        let value = &params[4..length];
+        match (param_type, value.len()) {
+            (IPV4_ADDRESS, 4) => {
+                addresses.push(Ipv4Addr::new(value[0], value[1], value[2], value[3]).to_string())
+            }
+            (IPV6_ADDRESS, 16) => {
+                let octets: [u8; 16] = value.try_into().unwrap_or([0; 16]);
+                addresses.push(Ipv6Addr::from(octets).to_string());
+            }
+            _ => {}
+        }
+        params = params.get(length.next_multiple_of(4)..).unwrap_or(&[]);
+    }
+    addresses
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn lists_init_addresses_within_chunk() {
+        let mut packet = vec![0x0B, 0x59, 0x0B, 0x59, 0, 0, 0, 0, 0, 0, 0, 0];
+        packet.extend_from_slice(&[INIT, 0, 0, 36]);
+        packet.extend_from_slice(&[0; 16]);
+        packet.extend_from_slice(&[0, 5, 0, 8, 10, 0, 0, 2]);
+        packet.extend_from_slice(&[0, 5, 0, 8, 10, 0, 0, 3]);
+        // Outside the declared chunk length, so it must be ignored.
+        packet.extend_from_slice(&[0, 5, 0, 8, 10, 0, 0, 4]);
+        assert_eq!(init_addresses(&packet), ["10.0.0.2", "10.0.0.3"]);
+        packet[12] = 0;
+        assert!(init_addresses(&packet).is_empty());
+    }
+}
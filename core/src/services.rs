@@ -0,0 +1,47 @@
+/// Well-known server ports and the service usually behind them.
+const SERVICES: [(u16, &str); 24] = [
+    (20, "ftp-data"),
+    (21, "ftp"),
+    (22, "ssh"),
+    (23, "telnet"),
+    (25, "smtp"),
+    (53, "dns"),
+    (67, "dhcp"),
+    (69, "tftp"),
+    (80, "http"),
+    (110, "pop3"),
+    (123, "ntp"),
+    (143, "imap"),
+    (161, "snmp"),
+    (389, "ldap"),
+    (443, "https"),
+    (445, "smb"),
+    (587, "submission"),
+    (993, "imaps"),
+    (995, "pop3s"),
+    (1433, "mssql"),
+    (3306, "mysql"),
+    (3389, "rdp"),
+    (5432, "postgresql"),
+    (8080, "http-alt"),
+];
+
+/// Names the service conventionally listening on `port`.
+pub fn service_name(port: u16) -> Option<&'static str> {
+    SERVICES
+        .binary_search_by_key(&port, |(port, _)| *port)
+        .ok()
+        .map(|index| SERVICES[index].1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn looks_up_well_known_ports() {
+        assert!(SERVICES.is_sorted_by_key(|(port, _)| *port));
+        assert_eq!(service_name(443), Some("https"));
+        assert_eq!(service_name(3306), Some("mysql"));
+        assert_eq!(service_name(51000), None);
+    }
+}
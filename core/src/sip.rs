@@ -0,0 +1,52 @@
+use crate::text::{first_line, truncate_line};
+
+const METHODS: &[&str] = &[
+    "INVITE",
+    "ACK",
+    "BYE",
+    "CANCEL",
+    "REGISTER",
+    "OPTIONS",
+    "PRACK",
+    "SUBSCRIBE",
+    "NOTIFY",
+    "PUBLISH",
+    "INFO",
+    "REFER",
+    "MESSAGE",
+    "UPDATE",
+];
+
+/// Returns the SIP request or status line, truncated for display.
+pub fn parse_sip(payload: &[u8]) -> Option<String> {
+    let line = first_line(payload)?;
+    let is_status = line
+        .strip_prefix("SIP/2.0 ")
+        .is_some_and(|rest| rest.len() >= 3 && rest.as_bytes()[..3].iter().all(u8::is_ascii_digit));
+    let is_request = line.ends_with(" SIP/2.0")
+        && line
+            .split_once(' ')
+            .is_some_and(|(method, uri)| METHODS.contains(&method) && uri.starts_with("sip"));
+    (is_status || is_request).then(|| truncate_line(line))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn recognizes_requests_and_responses() {
+        assert_eq!(
+            parse_sip(b"INVITE sip:bob@example.com SIP/2.0\r\nVia: x\r\n").as_deref(),
+            Some("INVITE sip:bob@example.com SIP/2.0")
+        );
+        assert_eq!(
+            parse_sip(b"SIP/2.0 200 OK\r\n").as_deref(),
+            Some("SIP/2.0 200 OK")
+        );
+    }
+    #[test]
+    fn rejects_other_text() {
+        assert!(parse_sip(b"GET / HTTP/1.1\r\n").is_none());
+        assert!(parse_sip(b"SIP/2.0 OK\r\n").is_none());
+    }
+}
@@ -0,0 +1,65 @@
+use crate::format_mac;
+
+/// Fields of a Linux cooked capture (`DLT_LINUX_SLL`) header and the network
+/// layer payload that follows it. The packet type and ARPHRD type are not
+/// needed for dissection and are skipped.
+pub struct SllHeader<'a> {
+    /// Link-layer source address, formatted like a MAC when present.
+    pub address: Option<String>,
+    pub protocol: u16,
+    pub payload: &'a [u8],
+}
+
+/// Parses the 16-byte SLL header. Returns `None` when the frame is too short
+/// to hold one.
+pub fn parse_sll_header(frame: &[u8]) -> Option<SllHeader<'_>> {
+    let header = frame.get(..16)?;
+    let read_u16 = |offset: usize| u16::from_be_bytes([header[offset], header[offset + 1]]);
+    let address_len = (read_u16(4) as usize).min(8);
+    let address = (address_len > 0).then(|| format_mac(&header[6..6 + address_len]));
+    Some(SllHeader {
+        address,
+        protocol: read_u16(14),
+        payload: &frame[16..],
+    })
+}
+
+/// Parses the 20-byte SLL2 header (`DLT_LINUX_SLL2`), which moves the
+/// protocol type to the front and adds the interface index.
+pub fn parse_sll2_header(frame: &[u8]) -> Option<SllHeader<'_>> {
+    let header = frame.get(..20)?;
+    let address_len = (header[11] as usize).min(8);
+    let address = (address_len > 0).then(|| format_mac(&header[12..12 + address_len]));
+    Some(SllHeader {
+        address,
+        protocol: u16::from_be_bytes([header[0], header[1]]),
+        payload: &frame[20..],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn parses_cooked_header() {
+        let frame = [
+            0, 4, 0, 1, 0, 6, 0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0, 0, 0x08, 0x00, 0x45,
+        ];
+        let sll = parse_sll_header(&frame).unwrap();
+        assert_eq!(sll.address.as_deref(), Some("00:11:22:33:44:55"));
+        assert_eq!(sll.protocol, 0x0800);
+        assert_eq!(sll.payload, [0x45]);
+        assert!(parse_sll_header(&frame[..15]).is_none());
+    }
+    #[test]
+    fn parses_sll2_header() {
+        let frame = [
+            0x86, 0xDD, 0, 0, 0, 0, 0, 2, 0, 1, 0, 6, 0, 0x11, 0x22, 0x33, 0x44, 0x55, 0, 0, 0x60,
+        ];
+        let sll = parse_sll2_header(&frame).unwrap();
+        assert_eq!(sll.protocol, 0x86DD);
+        assert_eq!(sll.address.as_deref(), Some("00:11:22:33:44:55"));
+        assert_eq!(sll.payload, [0x60]);
+        assert!(parse_sll2_header(&frame[..19]).is_none());
+    }
+}
@@ -0,0 +1,83 @@
+const HEADER_LEN: usize = 20;
+const MAGIC_COOKIE: [u8; 4] = [0x21, 0x12, 0xA4, 0x42];
+
+/// Recognizes a STUN (RFC 5389) or TURN message by its magic cookie and
+/// names its method and class, e.g. `STUN Binding Request`.
+pub fn parse_stun(payload: &[u8]) -> Option<String> {
+    if payload.len() < HEADER_LEN || payload[4..8] != MAGIC_COOKIE {
+        return None;
+    }
+    let message_type = u16::from_be_bytes([payload[0], payload[1]]);
+    let length = u16::from_be_bytes([payload[2], payload[3]]) as usize;
+    // The top two bits are always zero and attributes are 4-byte aligned.
+    if message_type & 0xC000 != 0
+        || !length.is_multiple_of(4)
+        || length > payload.len() - HEADER_LEN
+    {
+        return None;
+    }
+    // The class bits (C1 at bit 8, C0 at bit 4) are interleaved with the
+    // 12-bit method.
+    let method =
+        ((message_type & 0x3E00) >> 2) | ((message_type & 0x00E0) >> 1) | (message_type & 0x000F);
+    let class = match ((message_type >> 7) & 0x2) | ((message_type >> 4) & 0x1) {
+        0 => "Request",
+        1 => "Indication",
+        2 => "Success Response",
+        _ => "Error Response",
+    };
+    Some(format!("STUN {} {class}", method_name(method)))
+}
+
+fn method_name(method: u16) -> String {
+    match method {
+        0x001 => "Binding".into(),
+        0x003 => "Allocate".into(),
+        0x004 => "Refresh".into(),
+        0x006 => "Send".into(),
+        0x007 => "Data".into(),
+        0x008 => "CreatePermission".into(),
+        0x009 => "ChannelBind".into(),
+        _ => format!("method 0x{method:03X}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(message_type: u16, attributes: &[u8]) -> Vec<u8> {
+        let mut payload = message_type.to_be_bytes().to_vec();
+        payload.extend_from_slice(&(attributes.len() as u16).to_be_bytes());
+        payload.extend_from_slice(&MAGIC_COOKIE);
+        payload.extend_from_slice(&[7; 12]);
+        payload.extend_from_slice(attributes);
+        payload
+    }
+
+    #[test]
+    fn names_binding_request_and_response() {
+        assert_eq!(
+            parse_stun(&message(0x0001, &[])).as_deref(),
+            Some("STUN Binding Request")
+        );
+        assert_eq!(
+            parse_stun(&message(0x0101, &[0, 0x20, 0, 0])).as_deref(),
+            Some("STUN Binding Success Response")
+        );
+        assert_eq!(
+            parse_stun(&message(0x0113, &[])).as_deref(),
+            Some("STUN Allocate Error Response")
+        );
+    }
+
+    #[test]
+    fn rejects_missing_cookie_or_bad_length() {
+        let mut payload = message(0x0001, &[]);
+        payload[4] = 0;
+        assert!(parse_stun(&payload).is_none());
+        let mut overlong = message(0x0001, &[]);
+        overlong[3] = 8;
+        assert!(parse_stun(&overlong).is_none());
+    }
+}
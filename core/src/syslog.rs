@@ -0,0 +1,68 @@
+use crate::text::{first_line, truncate_line};
+
+const FACILITIES: [&str; 24] = [
+    "kern",
+    "user",
+    "mail",
+    "daemon",
+    "auth",
+    "syslog",
+    "lpr",
+    "news",
+    "uucp",
+    "cron",
+    "authpriv",
+    "ftp",
+    "ntp",
+    "security",
+    "console",
+    "solaris-cron",
+    "local0",
+    "local1",
+    "local2",
+    "local3",
+    "local4",
+    "local5",
+    "local6",
+    "local7",
+];
+const SEVERITIES: [&str; 8] = [
+    "emerg", "alert", "crit", "err", "warning", "notice", "info", "debug",
+];
+
+/// Decodes the `<PRI>` prefix of a syslog message into facility and
+/// severity, followed by the start of the message text.
+pub fn parse_syslog(payload: &[u8]) -> Option<String> {
+    let rest = payload.strip_prefix(b"<")?;
+    let end = rest.iter().position(|&byte| byte == b'>')?;
+    if !(1..=3).contains(&end) || !rest[..end].iter().all(u8::is_ascii_digit) {
+        return None;
+    }
+    let priority: usize = std::str::from_utf8(&rest[..end]).ok()?.parse().ok()?;
+    let facility = FACILITIES.get(priority / 8)?;
+    let severity = SEVERITIES[priority % 8];
+    let label = format!("Syslog {facility}.{severity}");
+    Some(match first_line(&rest[end + 1..]) {
+        Some(message) => format!("{label}: {}", truncate_line(message)),
+        None => label,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn decodes_priority_and_message() {
+        assert_eq!(
+            parse_syslog(b"<134>Oct 11 22:14:15 host app: started\n").as_deref(),
+            Some("Syslog local0.info: Oct 11 22:14:15 host app: started")
+        );
+        assert_eq!(parse_syslog(b"<0>").as_deref(), Some("Syslog kern.emerg"));
+    }
+    #[test]
+    fn rejects_invalid_priorities() {
+        assert!(parse_syslog(b"<192>too high").is_none());
+        assert!(parse_syslog(b"<1a>x").is_none());
+        assert!(parse_syslog(b"134>x").is_none());
+    }
+}
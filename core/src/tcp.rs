@@ -0,0 +1,226 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use crate::models::{PacketAnalysis, TcpHeader};
+
+pub const SYN: u8 = 0x02;
+pub const URG: u8 = 0x20;
+/// MSS below which a SYN's MSS is flagged as small.
+pub const SMALL_MSS: u16 = 1200;
+
+/// Options carried in a TCP header that the analysis cares about, also
+/// emitted as the `tcp_options` object of a packet's info.
+#[derive(Default, Serialize, Clone)]
+pub struct TcpOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mss: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_scale: Option<u8>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub sack_permitted: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timestamps: Option<(u32, u32)>,
+}
+
+impl TcpOptions {
+    /// Collects the options recorded on a decoded header, or `None` when the
+    /// segment carried none of them.
+    pub fn from_header(tcp: &TcpHeader) -> Option<TcpOptions> {
+        let options = TcpOptions {
+            mss: tcp.mss,
+            window_scale: tcp.window_scale,
+            sack_permitted: tcp.sack_permitted,
+            timestamps: tcp.timestamps,
+        };
+        let empty = options.mss.is_none()
+            && options.window_scale.is_none()
+            && !options.sack_permitted
+            && options.timestamps.is_none();
+        (!empty).then_some(options)
+    }
+}
+
+/// Walks the option area of a TCP header, stopping at End of Option List or
+/// the first option whose length overruns the header.
+pub fn parse_tcp_options(area: &[u8]) -> TcpOptions {
+    let mut options = TcpOptions::default();
+    let mut offset = 0;
+    while offset < area.len() {
+        match area[offset] {
+            0 => break,
+            1 => {
+                offset += 1;
+                continue;
+            }
+            kind => {
+                let Some(&length) = area.get(offset + 1) else {
+                    break;
+                };
+                let length = length as usize;
+                if length < 2 || offset + length > area.len() {
+                    break;
+                }
+                let value = &area[offset + 2..offset + length];
+                match (kind, value.len()) {
+                    (2, 2) => options.mss = Some(u16::from_be_bytes([value[0], value[1]])),
+                    // RFC 7323 caps the shift count at 14.
+                    (3, 1) => options.window_scale = Some(value[0].min(14)),
+                    (4, 0) => options.sack_permitted = true,
+                    (8, 8) => {
+                        let tsval = u32::from_be_bytes([value[0], value[1], value[2], value[3]]);
+                        let tsecr = u32::from_be_bytes([value[4], value[5], value[6], value[7]]);
+                        options.timestamps = Some((tsval, tsecr));
+                    }
+                    _ => {}
+                }
+                offset += length;
+            }
+        }
+    }
+    options
+}
+
+/// Lists the set control flags in Wireshark's order, e.g. `SYN, ACK`.
+/// Returns `None` when no flag we display is set.
+pub fn flag_names(flags: u8) -> Option<String> {
+    const NAMES: [(u8, &str); 6] = [
+        (0x01, "FIN"),
+        (SYN, "SYN"),
+        (0x04, "RST"),
+        (0x08, "PSH"),
+        (0x10, "ACK"),
+        (URG, "URG"),
+    ];
+    let names: Vec<&str> = NAMES
+        .iter()
+        .filter(|(bit, _)| flags & bit != 0)
+        .map(|(_, name)| *name)
+        .collect();
+    (!names.is_empty()).then(|| names.join(", "))
+}
+
+/// Path MTU implied by an MSS: the MSS plus minimal TCP and IP headers.
+pub fn implied_path_mtu(mss: u16, ipv6: bool) -> u32 {
+    u32::from(mss) + if ipv6 { 60 } else { 40 }
+}
+
+/// Remembers the window-scale factor each endpoint offered in its SYN so
+/// later segments can report the effective receive window.
+#[derive(Default)]
+pub struct TcpFlowTracker {
+    scales: HashMap<String, Option<u8>>,
+}
+
+impl TcpFlowTracker {
+    /// Records SYN options and fills in the effective window for `analysis`,
+    /// appending it to the summary. Packets without a TCP layer are ignored.
+    pub fn annotate(&mut self, analysis: &mut PacketAnalysis) {
+        let Some(tcp) = analysis.layers.tcp.as_mut() else {
+            return;
+        };
+        let effective = if tcp.flags & SYN != 0 {
+            self.scales
+                .insert(analysis.source.clone(), tcp.window_scale);
+            // The window in a SYN is never scaled.
+            u32::from(tcp.window)
+        } else {
+            // Scaling is only in effect once both sides have offered it. A
+            // peer whose SYN was not captured is assumed to have offered it.
+            let own = self.scales.get(&analysis.source).copied().flatten();
+            let peer = self.scales.get(&analysis.destination);
+            match (own, peer) {
+                (Some(scale), Some(Some(_)) | None) => u32::from(tcp.window) << scale,
+                _ => u32::from(tcp.window),
+            }
+        };
+        tcp.effective_window = effective;
+        analysis.summary.push_str(&format!(" [win {effective}]"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::DecodedLayers;
+
+    fn segment(
+        source: &str,
+        destination: &str,
+        flags: u8,
+        window: u16,
+        scale: Option<u8>,
+    ) -> PacketAnalysis {
+        PacketAnalysis {
+            source: source.to_string(),
+            destination: destination.to_string(),
+            protocol: "TCP".to_string(),
+            summary: "TCP".to_string(),
+            layers: DecodedLayers {
+                tcp: Some(TcpHeader {
+                    source_port: 0,
+                    destination_port: 0,
+                    sequence: None,
+                    acknowledgment: None,
+                    flags,
+                    window,
+                    window_scale: scale,
+                    effective_window: 0,
+                    mss: None,
+                    path_mtu: None,
+                    sack_permitted: false,
+                    timestamps: None,
+                    urgent_pointer: None,
+                }),
+                ..DecodedLayers::default()
+            },
+        }
+    }
+
+    #[test]
+    fn reads_window_scale_after_padding() {
+        let area = [2, 4, 5, 180, 1, 3, 3, 7, 0, 0];
+        assert_eq!(parse_tcp_options(&area).window_scale, Some(7));
+        assert_eq!(parse_tcp_options(&area).mss, Some(1460));
+        assert_eq!(parse_tcp_options(&[3, 9, 7]).window_scale, None);
+        assert!(parse_tcp_options(&[1, 4, 2, 0, 4, 2]).sack_permitted);
+        assert!(!parse_tcp_options(&[0, 4, 2]).sack_permitted);
+    }
+
+    #[test]
+    fn names_flags_in_display_order() {
+        assert_eq!(flag_names(SYN | 0x10).as_deref(), Some("SYN, ACK"));
+        assert_eq!(flag_names(0x11).as_deref(), Some("FIN, ACK"));
+        assert_eq!(flag_names(0x38).as_deref(), Some("PSH, ACK, URG"));
+        assert!(flag_names(0).is_none());
+    }
+
+    #[test]
+    fn scales_window_after_handshake() {
+        let mut tracker = TcpFlowTracker::default();
+        let mut syn = segment("a:1", "b:2", SYN, 64240, Some(7));
+        tracker.annotate(&mut syn);
+        assert_eq!(syn.summary, "TCP [win 64240]");
+        tracker.annotate(&mut segment("b:2", "a:1", SYN | 0x10, 65160, Some(7)));
+        let mut ack = segment("a:1", "b:2", 0x10, 502, None);
+        tracker.annotate(&mut ack);
+        assert_eq!(ack.summary, "TCP [win 64256]");
+        let mut unscaled = segment("c:3", "b:2", 0x10, 502, None);
+        tracker.annotate(&mut unscaled);
+        assert_eq!(unscaled.summary, "TCP [win 502]");
+    }
+
+    #[test]
+    fn assumes_scaling_when_peer_syn_unseen() {
+        let mut tracker = TcpFlowTracker::default();
+        tracker.annotate(&mut segment("a:1", "b:2", SYN, 64240, Some(7)));
+        let mut ack = segment("a:1", "b:2", 0x10, 502, None);
+        tracker.annotate(&mut ack);
+        assert_eq!(ack.summary, "TCP [win 64256]");
+        // A peer seen declining the option disables scaling.
+        tracker.annotate(&mut segment("b:2", "a:1", SYN | 0x10, 65160, None));
+        let mut ack = segment("a:1", "b:2", 0x10, 502, None);
+        tracker.annotate(&mut ack);
+        assert_eq!(ack.summary, "TCP [win 502]");
+    }
+}
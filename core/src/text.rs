@@ -0,0 +1,47 @@
+const MAX_LINE_CHARS: usize = 80;
+
+/// Returns the first line of a text-based payload, or `None` if it is empty
+/// or not printable ASCII.
+pub fn first_line(payload: &[u8]) -> Option<&str> {
+    let end = payload
+        .iter()
+        .position(|&byte| byte == b'\r' || byte == b'\n')
+        .unwrap_or(payload.len());
+    let line = std::str::from_utf8(&payload[..end]).ok()?;
+    if line.is_empty()
+        || !line
+            .bytes()
+            .all(|byte| (0x20..=0x7E).contains(&byte) || byte == b'\t')
+    {
+        return None;
+    }
+    Some(line)
+}
+
+/// Shortens a line for display, marking any cut with an ellipsis.
+pub fn truncate_line(line: &str) -> String {
+    if line.chars().count() <= MAX_LINE_CHARS {
+        return line.to_string();
+    }
+    let mut shortened: String = line.chars().take(MAX_LINE_CHARS).collect();
+    shortened.push('…');
+    shortened
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn first_line_stops_at_line_break() {
+        assert_eq!(
+            first_line(b"USER anonymous\r\nPASS x"),
+            Some("USER anonymous")
+        );
+        assert_eq!(first_line(&[0x16, 0x03, 0x01]), None);
+    }
+    #[test]
+    fn truncates_long_lines() {
+        let line = "x".repeat(100);
+        assert_eq!(truncate_line(&line).chars().count(), MAX_LINE_CHARS + 1);
+    }
+}
@@ -0,0 +1,137 @@
+const RECORD_HEADER_LEN: usize = 5;
+const HANDSHAKE: u8 = 22;
+const CLIENT_HELLO: u8 = 1;
+const SERVER_NAME: u16 = 0;
+
+/// Summarizes a TLS record carrying a ClientHello as
+/// `TLS ClientHello SNI=example.com`, or just `TLS ClientHello` when no
+/// server_name extension is present. Anything else yields `None`.
+pub fn parse_tls_client_hello(payload: &[u8]) -> Option<String> {
+    let header = payload.get(..RECORD_HEADER_LEN)?;
+    if header[0] != HANDSHAKE || header[1] != 0x03 {
+        return None;
+    }
+    // Records split across segments are read as far as they were captured.
+    let record_len = u16::from_be_bytes([header[3], header[4]]) as usize;
+    let end = payload.len().min(RECORD_HEADER_LEN + record_len);
+    let record = &payload[RECORD_HEADER_LEN..end];
+    if *record.first()? != CLIENT_HELLO {
+        return None;
+    }
+    // Handshake header (4), client version (2) and random (32).
+    let mut reader = Reader {
+        data: record,
+        offset: 38,
+    };
+    let session_id_len = reader.u8()? as usize;
+    reader.skip(session_id_len)?;
+    let cipher_suites_len = reader.u16()? as usize;
+    reader.skip(cipher_suites_len)?;
+    let compression_len = reader.u8()? as usize;
+    reader.skip(compression_len)?;
+    let sni = reader.u16().and_then(|extensions_len| {
+        let extensions = reader.take(extensions_len as usize)?;
+        find_server_name(extensions)
+    });
+    Some(match sni {
+        Some(name) => format!("TLS ClientHello SNI={name}"),
+        None => "TLS ClientHello".to_string(),
+    })
+}
+
+/// Returns the first host_name entry of the server_name extension.
+fn find_server_name(extensions: &[u8]) -> Option<String> {
+    let mut reader = Reader {
+        data: extensions,
+        offset: 0,
+    };
+    while let Some(extension_type) = reader.u16() {
+        let length = reader.u16()? as usize;
+        let body = reader.take(length)?;
+        if extension_type != SERVER_NAME {
+            continue;
+        }
+        let mut names = Reader {
+            data: body,
+            offset: 2,
+        };
+        while let Some(name_type) = names.u8() {
+            let name_len = names.u16()? as usize;
+            let name = names.take(name_len)?;
+            if name_type == 0 {
+                return Some(String::from_utf8_lossy(name).into_owned());
+            }
+        }
+        return None;
+    }
+    None
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, length: usize) -> Option<&'a [u8]> {
+        let bytes = self.data.get(self.offset..self.offset + length)?;
+        self.offset += length;
+        Some(bytes)
+    }
+
+    fn skip(&mut self, length: usize) -> Option<()> {
+        self.take(length).map(|_| ())
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.take(1).map(|bytes| bytes[0])
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        self.take(2)
+            .map(|bytes| u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn client_hello(extensions: &[u8]) -> Vec<u8> {
+        let mut body = vec![0x03, 0x03];
+        body.extend_from_slice(&[0; 32]);
+        body.extend_from_slice(&[0, 0, 2, 0x13, 0x01, 1, 0]);
+        body.extend_from_slice(&(extensions.len() as u16).to_be_bytes());
+        body.extend_from_slice(extensions);
+        let mut handshake = vec![CLIENT_HELLO, 0];
+        handshake.extend_from_slice(&(body.len() as u16).to_be_bytes());
+        handshake.extend_from_slice(&body);
+        let mut record = vec![HANDSHAKE, 0x03, 0x01];
+        record.extend_from_slice(&(handshake.len() as u16).to_be_bytes());
+        record.extend_from_slice(&handshake);
+        record
+    }
+
+    #[test]
+    fn extracts_server_name() {
+        let mut extensions = vec![0x00, 0x17, 0, 0];
+        extensions.extend_from_slice(&[0, 0, 0, 16, 0, 14, 0, 0, 11]);
+        extensions.extend_from_slice(b"example.com");
+        assert_eq!(
+            parse_tls_client_hello(&client_hello(&extensions)).as_deref(),
+            Some("TLS ClientHello SNI=example.com")
+        );
+        assert_eq!(
+            parse_tls_client_hello(&client_hello(&[])).as_deref(),
+            Some("TLS ClientHello")
+        );
+    }
+
+    #[test]
+    fn ignores_other_records() {
+        let mut server_hello = client_hello(&[]);
+        server_hello[5] = 2;
+        assert!(parse_tls_client_hello(&server_hello).is_none());
+        assert!(parse_tls_client_hello(b"GET / HTTP/1.1\r\n").is_none());
+    }
+}
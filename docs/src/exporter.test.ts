@@ -74,4 +74,23 @@ describe("createPacketExport", () => {
     const payload = new Uint8Array(buffer, 40, packet.payload.length);
     expect(Array.from(payload)).toEqual(Array.from(packet.payload));
   });
+
+  it("rebases PCAP timestamps to the earliest packet when requested", async () => {
+    const packets = [
+      createSamplePacket({ time: "1700000000.900000" }),
+      createSamplePacket({ time: "1700000002.100001" }),
+    ];
+    const { blob } = createPacketExport(packets, {
+      format: "pcap",
+      rebaseTime: true,
+    });
+
+    const view = new DataView(await blob.arrayBuffer());
+    const secondRecord = 24 + 16 + createSamplePacket().payload.length;
+
+    expect(view.getUint32(24, true)).toBe(0);
+    expect(view.getUint32(28, true)).toBe(0);
+    expect(view.getUint32(secondRecord, true)).toBe(1);
+    expect(view.getUint32(secondRecord + 4, true)).toBe(200001);
+  });
 });
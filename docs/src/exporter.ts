@@ -5,6 +5,11 @@ export type PacketExportFormat = "json" | "pcap";
 export interface PacketExportOptions {
   format?: PacketExportFormat;
   filenamePrefix?: string;
+  /**
+   * Shift PCAP record timestamps so the earliest packet starts at zero,
+   * removing absolute capture times while keeping relative spacing exact.
+   */
+  rebaseTime?: boolean;
 }
 
 export interface PacketExportResult {
@@ -125,9 +130,32 @@ const parseTimestamp = (
   return { seconds: fallbackIndex, microseconds: 0 };
 };
 
+const rebaseTimestamps = (
+  timestamps: Array<{ seconds: number; microseconds: number }>,
+): Array<{ seconds: number; microseconds: number }> => {
+  const base = timestamps.reduce((earliest, timestamp) =>
+    timestamp.seconds < earliest.seconds ||
+    (timestamp.seconds === earliest.seconds &&
+      timestamp.microseconds < earliest.microseconds)
+      ? timestamp
+      : earliest,
+  );
+
+  return timestamps.map(({ seconds, microseconds }) => {
+    let rebasedSeconds = seconds - base.seconds;
+    let rebasedMicroseconds = microseconds - base.microseconds;
+    if (rebasedMicroseconds < 0) {
+      rebasedSeconds -= 1;
+      rebasedMicroseconds += 1_000_000;
+    }
+    return { seconds: rebasedSeconds, microseconds: rebasedMicroseconds };
+  });
+};
+
 const createPcapExport = (
   packets: PacketRecord[],
   filenamePrefix: string,
+  rebaseTime: boolean,
 ): PacketExportResult => {
   const headerLength = 24;
   const recordHeaderLength = 16;
@@ -156,9 +184,15 @@ const createPcapExport = (
   offset += 4;
 
   const bufferView = new Uint8Array(buffer);
+  const parsedTimestamps = packets.map((packet, index) =>
+    parseTimestamp(packet.time, index),
+  );
+  const timestamps = rebaseTime
+    ? rebaseTimestamps(parsedTimestamps)
+    : parsedTimestamps;
 
   packets.forEach((packet, index) => {
-    const { seconds, microseconds } = parseTimestamp(packet.time, index);
+    const { seconds, microseconds } = timestamps[index];
     const payload = packet.payload;
     const capturedLength = payload.length;
     const reportedLength = Number.isFinite(packet.length)
@@ -192,7 +226,11 @@ export const createPacketExport = (
   packets: PacketRecord[],
   options: PacketExportOptions = {},
 ): PacketExportResult => {
-  const { format = "json", filenamePrefix = DEFAULT_PREFIX } = options;
+  const {
+    format = "json",
+    filenamePrefix = DEFAULT_PREFIX,
+    rebaseTime = false,
+  } = options;
 
   if (!Array.isArray(packets) || packets.length === 0) {
     throw new Error("No packets available to export.");
@@ -202,7 +240,7 @@ export const createPacketExport = (
     case "json":
       return createJsonExport(packets, filenamePrefix);
     case "pcap":
-      return createPcapExport(packets, filenamePrefix);
+      return createPcapExport(packets, filenamePrefix, rebaseTime);
     default:
       throw new Error(`Unsupported export format: ${String(format)}`);
   }